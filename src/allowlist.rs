@@ -0,0 +1,74 @@
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+/// Leaf of the allowlist Merkle tree for `wallet`
+pub fn allowlist_leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0], wallet.as_ref()]).to_bytes()
+}
+
+//hashes two nodes in sorted order, so that proofs don't need to tell left from right
+fn hash_nodes(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+/// Check that `proof` links the leaf of `wallet` to the Merkle `root` of an allowlist
+pub fn verify_allowlist_proof(root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let node = proof.iter().fold(allowlist_leaf(wallet), |node, sibling| {
+        hash_nodes(&node, sibling)
+    });
+    &node == root
+}
+
+/// Merkle tree of the wallets allowed to mint during an allowlist phase, built off-chain
+/// to get the root stored by the drop and the proofs passed to `MintFromDrop`
+#[derive(Debug, PartialEq, Clone)]
+pub struct AllowlistTree {
+    //leaves first, a node without sibling is moved up as is
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl AllowlistTree {
+    /// Build the tree of `wallets`
+    pub fn new(wallets: &[Pubkey]) -> Self {
+        let mut layers = vec![wallets.iter().map(allowlist_leaf).collect::<Vec<_>>()];
+
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let parents = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_nodes(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(parents);
+        }
+
+        Self { layers }
+    }
+
+    /// Merkle root of the tree, all zeroes if empty
+    pub fn root(&self) -> [u8; 32] {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Proof that `wallet` is in the tree, or `None` if it isn't
+    pub fn proof(&self, wallet: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        let leaf = allowlist_leaf(wallet);
+        let mut index = self.layers[0].iter().position(|node| node == &leaf)?;
+
+        let mut proof = vec![];
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+
+        Some(proof)
+    }
+}
@@ -2,7 +2,7 @@ use solana_program::program_error::ProgramError;
 use thiserror::Error;
 
 #[derive(Error, Debug, Copy, Clone)]
-pub enum GloweError{
+pub enum GloweError {
     /// Invalid instruction
     #[error("Invalid instruction")]
     InvalidInstruction,
@@ -14,6 +14,154 @@ pub enum GloweError{
     /// Provided account did not match the expected account
     #[error("Provided account did not match the expected account")]
     AccountMismatch,
+
+    /// The NFT URL could not be parsed
+    #[error("Provided URL is malformed")]
+    InvalidUrl,
+
+    /// The NFT URL is longer than `MAX_URL_LENGTH`
+    #[error("Provided URL is too long")]
+    UrlTooLong,
+
+    /// The NFT URL scheme is not one of the allowed schemes
+    #[error("Provided URL scheme is not allowed")]
+    UnsupportedUrlScheme,
+
+    /// The NFT was not minted as part of the provided collection
+    #[error("NFT does not belong to the provided collection")]
+    CollectionMismatch,
+
+    /// The royalties are higher than 100%
+    #[error("Seller fee basis points must not exceed 10000")]
+    InvalidSellerFee,
+
+    /// More creators than `MAX_CREATORS` were provided
+    #[error("Too many creators")]
+    TooManyCreators,
+
+    /// The same creator was provided more than once
+    #[error("Duplicate creator address")]
+    DuplicateCreator,
+
+    /// The creators shares do not add up to 100%
+    #[error("Creator shares must add up to 10000 basis points")]
+    InvalidCreatorShares,
+
+    /// The signer is not one of the creators of the NFT
+    #[error("Signer is not a creator of the NFT")]
+    CreatorNotFound,
+
+    /// The maximum supply of a master edition is out of range
+    #[error("Master edition max supply must be between 1 and MAX_EDITION_SUPPLY")]
+    InvalidMaxSupply,
+
+    /// The edition number is 0 or above the master edition max supply
+    #[error("Invalid edition number")]
+    InvalidEditionNumber,
+
+    /// The edition number was already printed
+    #[error("Edition already printed")]
+    EditionAlreadyPrinted,
+
+    /// The signer does not hold the NFT
+    #[error("Signer does not own the NFT")]
+    NotNftOwner,
+
+    /// The signer is not the update authority of the metadata
+    #[error("Signer is not the update authority")]
+    InvalidUpdateAuthority,
+
+    /// The metadata was made immutable
+    #[error("Metadata is immutable")]
+    MetadataImmutable,
+
+    /// The signer is not the freeze delegate of the NFT
+    #[error("Signer is not the freeze delegate")]
+    InvalidFreezeDelegate,
+
+    /// Soulbound NFTs can't be frozen, thawed or burned by their holder, only revoked
+    #[error("NFT is soulbound")]
+    SoulboundNft,
+
+    /// Only soulbound NFTs can be revoked
+    #[error("NFT is not soulbound")]
+    NotSoulbound,
+
+    /// The signer is not the minter of the NFT
+    #[error("Signer is not the issuer of the NFT")]
+    InvalidIssuer,
+
+    /// The token program doesn't support the requested extension
+    #[error("Extension requires the Token-2022 program")]
+    UnsupportedExtension,
+
+    /// The token account is neither uninitialized nor empty and owned by the payer
+    #[error("Token account can't be reclaimed")]
+    NotReclaimable,
+
+    /// Another NFT already registered the name
+    #[error("Name already registered")]
+    NameAlreadyRegistered,
+
+    /// The name can only be released once its NFT is burned
+    #[error("NFT holding the name is not burned")]
+    NameStillInUse,
+
+    /// The program was paused by its admin
+    #[error("Program is paused")]
+    ProgramPaused,
+
+    /// The signer is not the admin of the program
+    #[error("Signer is not the admin")]
+    InvalidAdmin,
+
+    /// The signer is not the upgrade authority of the program
+    #[error("Signer is not the upgrade authority")]
+    InvalidUpgradeAuthority,
+
+    /// The mint fee doesn't fit in a u64
+    #[error("Mint fee overflow")]
+    MintFeeOverflow,
+
+    /// The settings of the drop are inconsistent
+    #[error("Invalid drop settings")]
+    InvalidDrop,
+
+    /// The drop can't be minted from yet
+    #[error("Drop not live yet")]
+    DropNotLive,
+
+    /// The drop can't be minted from anymore
+    #[error("Drop ended")]
+    DropEnded,
+
+    /// Every item of the drop was minted
+    #[error("Drop sold out")]
+    DropSoldOut,
+
+    /// The minter isn't part of the allowlist of the current phase
+    #[error("Not allowlisted")]
+    NotAllowlisted,
+
+    /// The minter already minted the items allowed during the current phase
+    #[error("Allowlist limit reached")]
+    AllowlistLimitReached,
+
+    /// The minter already minted the items allowed per wallet by the drop
+    #[error("Wallet mint limit reached")]
+    WalletLimitReached,
+
+    /// The minter doesn't hold an NFT accepted by the token gate of the drop
+    #[error("Token gate not passed")]
+    TokenGateNotPassed,
+
+    /// The NFT was already used to pass the token gate of the drop
+    #[error("Gate NFT already used")]
+    GateNftAlreadyUsed,
+
+    /// The NFT name is longer than `MAX_NAME_LENGTH`
+    #[error("Provided name is too long")]
+    NameTooLong,
 }
 
 impl From<GloweError> for ProgramError {
@@ -1,12 +1,66 @@
-use crate::errors::GloweError as Error;
+use crate::{
+    errors::GloweError,
+    state::{
+        Creator, DropItems, DropSettings, GateAction, GateRequirement, NftDrop,
+        DROP_URL_PLACEHOLDER,
+    },
+};
 use solana_program::{
+    hash::hash,
     instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
+    program_pack::Pack,
     pubkey::Pubkey,
 };
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+/// Maximum number of NFTs minted by a single `MintBatch` instruction built by
+/// `mint_batches`, which also keeps each transaction within `MAX_TRANSACTION_SIZE`
+pub const MAX_MINT_BATCH_ITEMS: usize = 5;
+
+/// Maximum size in bytes of a serialized transaction
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Optional settings of a minted NFT
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone, Default)]
+pub struct MintOptions {
+    /// Collection the NFT belongs to, to be verified by the collection authority
+    pub collection: Option<Pubkey>,
+    /// Creators of the NFT, their shares must add up to `MAX_BASIS_POINTS`.
+    /// Creators marked as verified must co-sign the mint
+    pub creators: Vec<Creator>,
+    /// Royalties paid to the creators on secondary sales, in basis points
+    pub seller_fee_basis_points: u16,
+    /// Makes the NFT a master edition that can be printed up to the given number of times
+    pub max_editions: Option<u64>,
+    /// Account allowed to update the metadata, defaults to the minter
+    pub update_authority: Option<Pubkey>,
+    /// Prevents any update of the metadata
+    pub immutable: bool,
+    /// Account allowed to freeze and thaw the NFT through the freeze authority PDA
+    pub freeze_delegate: Option<Pubkey>,
+    /// Binds the NFT to the account of its owner, without their signature. Only the minter
+    /// can then revoke it, through the freeze authority PDA set as permanent delegate of
+    /// the mint. Requires Token-2022, not supported by `Mint2`
+    pub soulbound: bool,
+    /// Mints the NFT to the associated token account of the owner, shown by wallets,
+    /// instead of a token account PDA. Ignored by `Mint2`
+    pub associated_token_account: bool,
+    /// Prevents any transfer of the NFT, requires Token-2022
+    pub non_transferable: bool,
+    /// Registers the normalized name of the NFT so no other minter can use it
+    pub register_name: bool,
+}
+
+/// Instructions of the program. Except for `Initialize`, `SetAdmin`, `SetPaused`,
+/// `SetMintFee` and `WithdrawTreasury`, every instruction expects the config PDA as its
+/// last account, after the listed ones. `Mint`, `Mint2`, `MintBatch`, `MintFromDrop` and
+/// `PrintEdition` also expect the writable treasury PDA right before the config PDA,
+/// receiving the mint fee.
+///
+/// These trailing accounts are a breaking change: clients built before the config was
+/// introduced must append them, or their instructions fail, see `CHANGELOG.md`
 #[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
 pub enum GloweInstruction {
     /// Mints an NFT taking care of creating the necessary accounts (still need to be passed!!)
@@ -14,15 +68,25 @@ pub enum GloweInstruction {
     /// Accounts expected:
     /// 0. `[signer]` The account of the user minting
     /// 1. `[]` The account that will receive the NFT
-    /// 3. `[writable]` The PDA used for minting
-    /// 4. `[writable]` The PDA used to store the token
-    /// 5. `[]` The token program (SPL)
-    /// 6. `[]` The System program
-    /// 7. `[]` The Rent sysvar, needed by the token program
+    /// 2. `[writable]` The PDA used for minting
+    /// 3. `[writable]` The PDA used to store the token, or the associated token account of
+    ///    the owner if `options.associated_token_account` is set
+    /// 4. `[]` The token program, SPL Token or Token-2022
+    /// 5. `[]` The System program
+    /// 6. `[]` The Rent sysvar, needed by the token program
+    /// 7. `[writable]` The PDA used to store the NFT metadata
+    /// 8. `[writable]` Only if `options.max_editions` is set, the master edition PDA
+    /// 9. `[writable]` Only if `options.max_editions` is set, the edition marker PDA
+    /// 10. `[]` Only if `options.associated_token_account` is set, the associated token account program
+    /// 11. `[]` The name record PDA of the name, writable and created if
+    ///     `options.register_name` is set. The name can't be registered by another NFT
+    /// 12. `[signer]` Optional, the creators marked as verified in `options`, if not
+    ///     already signing as one of the accounts above
     Mint {
         /// Amount of a specific NFT to mint
         name: String,
         url: String,
+        options: MintOptions,
     },
 
     /// Mint an NFT
@@ -30,25 +94,330 @@ pub enum GloweInstruction {
     /// Accounts expected:
     /// 0. `[signer]` The account of the user minting
     /// 1. `[]` The account that will receive the NFT
-    /// 3. `[writable]` The account to used for minting, the owner must be the token program
-    /// 4. `[writable]` The account to used to store the token, the owner must be the token program
-    /// 5. `[]` The token program (SPL)
-    /// 6. `[]` The Rent sysvar, needed by the token program
+    /// 2. `[writable]` The account to used for minting, the owner must be the token program
+    /// 3. `[writable]` The account to used to store the token, the owner must be the token program
+    /// 4. `[]` The token program, SPL Token or Token-2022
+    /// 5. `[]` The Rent sysvar, needed by the token program
+    /// 6. `[writable]` The PDA used to store the NFT metadata
+    /// 7. `[]` The System program
+    /// 8. `[writable]` Only if `options.max_editions` is set, the master edition PDA
+    /// 9. `[writable]` Only if `options.max_editions` is set, the edition marker PDA
+    /// 10. `[]` The name record PDA of the name, writable and created if
+    ///     `options.register_name` is set. The name can't be registered by another NFT
+    /// 11. `[signer]` Optional, the creators marked as verified in `options`, if not
+    ///     already signing as one of the accounts above
     Mint2 {
         /// Amount of a specific NFT to mint
         name: String,
         url: String,
+        options: MintOptions,
+    },
+
+    /// Transfer an NFT minted with `Mint` to a new owner, creating the token account
+    /// of the new owner if needed and closing the one of the current owner
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The current owner of the NFT, pays for the new token account
+    /// 1. `[]` The account that will receive the NFT
+    /// 2. `[]` The mint of the NFT
+    /// 3. `[]` The metadata account of the NFT
+    /// 4. `[writable]` The PDA currently storing the token
+    /// 5. `[writable]` The PDA that will store the token
+    /// 6. `[]` The token program, SPL Token or Token-2022
+    /// 7. `[]` The System program
+    /// 8. `[]` The Rent sysvar, needed by the token program
+    Transfer,
+
+    /// Burn an NFT, closing its token account and metadata, and its master edition and
+    /// edition marker if it is a master edition.
+    /// The mint account can't be closed by the token program and is left as is
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The current owner of the NFT
+    /// 1. `[writable]` The mint of the NFT
+    /// 2. `[writable]` The metadata account of the NFT
+    /// 3. `[writable]` The account storing the token
+    /// 4. `[writable]` The account that will receive the lamports of the closed accounts
+    /// 5. `[]` The token program, SPL Token or Token-2022
+    /// 6. `[writable]` The master edition PDA of the NFT, closed if it exists
+    /// 7. `[writable]` The edition marker PDA of the NFT, closed if it exists
+    Burn,
+
+    /// Create a collection to group NFTs under
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The authority of the collection, pays for the collection account
+    /// 1. `[writable]` The PDA used to store the collection
+    /// 2. `[]` The System program
+    /// 3. `[]` The Rent sysvar
+    CreateCollection {
+        /// Name of the collection
+        name: String,
+        /// URL associated to the collection
+        url: String,
+    },
+
+    /// Mark an NFT as verified member of the collection it was minted with
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The authority of the collection
+    /// 1. `[]` The collection account
+    /// 2. `[]` The mint of the NFT
+    /// 3. `[writable]` The metadata account of the NFT
+    VerifyCollectionItem,
+
+    /// Mark the signing creator as verified on an NFT listing it as creator
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The creator
+    /// 1. `[]` The mint of the NFT
+    /// 2. `[writable]` The metadata account of the NFT
+    SignCreator,
+
+    /// Print a numbered edition of a master edition as a new NFT, see `print_edition_name`
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The owner of the master edition, pays for the print accounts
+    ///    and the mint fee
+    /// 1. `[]` The account that will receive the print
+    /// 2. `[]` The mint of the master edition
+    /// 3. `[]` The metadata account of the master edition
+    /// 4. `[writable]` The master edition account
+    /// 5. `[writable]` The edition marker account
+    /// 6. `[]` The account storing the master edition token
+    /// 7. `[writable]` The PDA used for minting the print
+    /// 8. `[writable]` The PDA used to store the print token
+    /// 9. `[writable]` The PDA used to store the print metadata
+    /// 10. `[]` The name record PDA of the print, its name can't be registered by another NFT
+    /// 11. `[]` The token program, SPL Token or Token-2022
+    /// 12. `[]` The System program
+    /// 13. `[]` The Rent sysvar, needed by the token program
+    PrintEdition {
+        /// Number of the edition to print, between 1 and the max supply
+        edition: u64,
+    },
+
+    /// Update the metadata of an NFT, the fields left to `None` are unchanged
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The update authority of the NFT, pays for any metadata growth
+    /// 1. `[]` The mint of the NFT
+    /// 2. `[writable]` The metadata account of the NFT
+    /// 3. `[]` The System program
+    /// 4. `[]` The Rent sysvar
+    UpdateMetadata {
+        /// New name to display, at most `MAX_NAME_LENGTH` bytes
+        display_name: Option<String>,
+        /// New URL
+        url: Option<String>,
+        /// New update authority
+        update_authority: Option<Pubkey>,
+        /// Set to `false` to make the metadata permanently immutable
+        is_mutable: Option<bool>,
+    },
+
+    /// Freeze the token account holding an NFT minted with a freeze delegate
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The freeze delegate of the NFT
+    /// 1. `[]` The mint of the NFT
+    /// 2. `[writable]` The metadata account of the NFT
+    /// 3. `[writable]` The account storing the token
+    /// 4. `[]` The freeze authority PDA of the mint
+    /// 5. `[]` The token program, SPL Token or Token-2022
+    Freeze,
+
+    /// Thaw the token account holding an NFT minted with a freeze delegate
+    ///
+    /// Accounts expected: same as `Freeze`
+    Thaw,
+
+    /// Burn a soulbound NFT and close its metadata. The emptied token account belongs
+    /// to the holder and is left open
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The minter of the NFT, receives the lamports of the metadata
+    /// 1. `[writable]` The mint of the NFT
+    /// 2. `[writable]` The metadata account of the NFT
+    /// 3. `[writable]` The account storing the token
+    /// 4. `[]` The freeze authority PDA of the mint, its permanent delegate
+    /// 5. `[]` The token program, Token-2022
+    Revoke,
+
+    /// Mint several NFTs with the default options, see `mint_batches` to split
+    /// a large list over several transactions
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The account of the user minting
+    /// 1. `[]` The account that will receive the NFTs
+    /// 2. `[]` The token program, SPL Token or Token-2022
+    /// 3. `[]` The System program
+    /// 4. `[]` The Rent sysvar, needed by the token program
+    /// 5. `[writable]` The mint PDA of the first NFT
+    /// 6. `[writable]` The token PDA of the first NFT
+    /// 7. `[writable]` The metadata PDA of the first NFT
+    /// 8. `[]` The name record PDA of the first NFT, its name can't be registered by
+    ///    another NFT
+    /// 9. ... the mint, token, metadata and name record PDAs of the next NFTs, in the order
+    ///    of `items`
+    MintBatch {
+        /// Name and URL of each NFT
+        items: Vec<(String, String)>,
+    },
+
+    /// Return to the payer the lamports of a token account created for `Mint2` and left
+    /// uninitialized or empty, see `is_reclaimable`. Uninitialized accounts are first
+    /// initialized with `mint` and the payer as owner so the token program can close them.
+    /// Mint accounts can't be closed by the token program and are left as is
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer, owner of the token account if initialized
+    /// 1. `[signer, writable]` The token account to close, only needs to sign if uninitialized
+    /// 2. `[]` The mint of the token account, or any initialized mint if uninitialized
+    /// 3. `[]` The token program, SPL Token or Token-2022
+    /// 4. `[]` The Rent sysvar, needed by the token program
+    Reclaim,
+
+    /// Release a name registered with `options.register_name` once its NFT is burned,
+    /// returning the lamports of the name record to the registrant
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The registrant of the name
+    /// 1. `[]` The mint of the burned NFT
+    /// 2. `[writable]` The name record PDA
+    ReleaseName,
+
+    /// Create the config of the program, to be called once right after deployment
+    /// by the upgrade authority of the program
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The upgrade authority of the program, becomes its admin and
+    ///    pays for the config account and the rent exemption of the treasury
+    /// 1. `[writable]` The config PDA
+    /// 2. `[writable]` The treasury PDA
+    /// 3. `[]` The System program
+    /// 4. `[]` The Rent sysvar
+    /// 5. `[]` The program data account of the program, see `derive_program_data_account`
+    Initialize,
+
+    /// Hand over the admin role of the program
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The current admin of the program
+    /// 1. `[writable]` The config PDA
+    SetAdmin {
+        /// The new admin
+        admin: Pubkey,
+    },
+
+    /// Pause or resume every instruction but the config ones
+    ///
+    /// Accounts expected: same as `SetAdmin`
+    SetPaused {
+        /// Whether the program is paused
+        paused: bool,
+    },
+
+    /// Change the lamports paid to the treasury for each minted NFT
+    ///
+    /// Accounts expected: same as `SetAdmin`
+    SetMintFee {
+        /// The new mint fee, in lamports
+        mint_fee: u64,
+    },
+
+    /// Move the lamports of the treasury above its rent exemption
+    ///
+    /// Accounts expected:
+    /// 0. `[signer]` The admin of the program
+    /// 1. `[]` The config PDA
+    /// 2. `[writable]` The treasury PDA
+    /// 3. `[writable]` The account receiving the lamports
+    /// 4. `[]` The System program
+    /// 5. `[]` The Rent sysvar
+    WithdrawTreasury,
+
+    /// Create a drop selling NFTs minted one by one by the buyers, see `MintFromDrop`
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The authority of the drop, pays for the drop account
+    /// 1. `[writable]` The PDA used to store the drop
+    /// 2. `[]` The System program
+    /// 3. `[]` The Rent sysvar
+    CreateDrop {
+        /// Name of the drop
+        name: String,
+        /// Price, supply, schedule and items of the drop
+        settings: DropSettings,
+    },
+
+    /// Mint the next item of a drop to the buyer, paying its price to the drop authority.
+    /// The item is minted like `Mint` with the default options, the drop authority being
+    /// the update authority of its metadata. During an allowlist phase, the buyer must be
+    /// part of the allowlist of the phase, see `AllowlistTree`
+    ///
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The buyer, pays for the item and receives it
+    /// 1. `[writable]` The drop PDA
+    /// 2. `[writable]` The authority of the drop, receiving the price
+    /// 3. `[writable]` The mint PDA of the item, derived from the drop PDA instead of a minter
+    /// 4. `[writable]` The token PDA of the item, derived from the drop PDA instead of a minter
+    /// 5. `[writable]` The metadata PDA of the item
+    /// 6. `[]` The name record PDA of the item, its name can't be registered by another NFT
+    /// 7. `[]` The token program, SPL Token or Token-2022
+    /// 8. `[]` The System program
+    /// 9. `[]` The Rent sysvar, needed by the token program
+    /// 10. `[]` The mint counter PDA of the buyer for the drop, writable and counting the
+    ///     items of the buyer only if `settings.wallet_limit` is set
+    /// 11. `[writable]` Only if `settings.token_payment` is set, the token account of the buyer
+    ///     paying the tokens
+    /// 12. `[writable]` Only if `settings.token_payment` is set, the token account of the drop
+    ///     receiving the tokens
+    /// 13. `[]` Only if `settings.token_payment` is set, the mint of the tokens
+    /// 14. `[]` Only if `settings.token_payment` is set, the token program of the tokens
+    /// 15. `[writable]` Only if `settings.token_gate` is set, the token account of the buyer
+    ///     holding the NFT presented
+    /// 16. `[writable]` Only if `settings.token_gate` is set, the mint of the NFT presented
+    /// 17. `[]` Only if `settings.token_gate` is set, the token program of the NFT presented,
+    ///     SPL Token or Token-2022
+    /// 18. `[]` Only if the token gate accepts a collection, the metadata PDA of the NFT
+    /// 19. `[writable]` Only if the token gate marks the NFT as used, the gate record PDA
+    /// 20. `[writable]` Only during an allowlist phase, the redemption PDA of the buyer
+    ///     for the phase
+    MintFromDrop {
+        /// Proof that the buyer is allowlisted, during an allowlist phase
+        proof: Vec<[u8; 32]>,
     },
 }
 
-pub(crate) fn derive_mint_account_internal(
+/// Version of the seeds used to derive the mint and token accounts.
+///
+/// Accounts derived before the version was introduced used the raw NFT name as seed,
+/// see `derive_legacy_mint_account` and `derive_legacy_token_account`
+pub const SEED_VERSION: &[u8] = b"v2";
+
+/// Normalize an NFT name before hashing it, so that names differing
+/// only by case or surrounding whitespace resolve to the same accounts
+pub fn normalize_nft_name(nft_name: &str) -> String {
+    nft_name.trim().to_lowercase()
+}
+
+/// Hash the normalized NFT name to use it as a seed, as names can exceed `MAX_SEED_LEN`
+pub fn hash_nft_name(nft_name: &str) -> [u8; 32] {
+    hash(normalize_nft_name(nft_name).as_bytes()).to_bytes()
+}
+
+/// Derive the mint account of an NFT for the given program, returning the bump seed too.
+/// `payer` is the minter of the NFT, or the drop PDA for drop items
+pub fn derive_mint_account(
     program_id: &Pubkey,
     token_program_id: &Pubkey,
     payer: &Pubkey,
     nft_name: &str,
 ) -> (Pubkey, u8) {
+    let name_seed = hash_nft_name(nft_name);
     Pubkey::find_program_address(
-        &derive_mint_account_seeds(program_id, token_program_id, payer, nft_name),
+        &derive_mint_account_seeds(program_id, token_program_id, payer, &name_seed),
         program_id,
     )
 }
@@ -57,11 +426,12 @@ pub(crate) fn derive_mint_account_seeds<'a>(
     program_id: &'a Pubkey,
     token_program_id: &'a Pubkey,
     payer: &'a Pubkey,
-    nft_name: &'a str,
-) -> [&'a [u8]; 6] {
+    name_seed: &'a [u8],
+) -> [&'a [u8]; 7] {
     [
         b"glowenft",
-        nft_name.as_bytes(),
+        SEED_VERSION,
+        name_seed,
         b"mint",
         program_id.as_ref(),
         token_program_id.as_ref(),
@@ -69,26 +439,45 @@ pub(crate) fn derive_mint_account_seeds<'a>(
     ]
 }
 
-/// Retrieve the mint account
-pub fn get_mint_account(minter: &Pubkey, nft_name: &str) -> Pubkey {
-    derive_mint_account_internal(
-        &Pubkey::new_from_array([42; 32]),
-        &spl_token::id(),
-        minter,
-        nft_name,
+/// Derive the mint account of an NFT minted before `SEED_VERSION` was introduced
+///
+/// Returns `None` if `nft_name` is too long to be used as a seed
+pub fn derive_legacy_mint_account(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    payer: &Pubkey,
+    nft_name: &str,
+) -> Option<(Pubkey, u8)> {
+    Pubkey::try_find_program_address(
+        &[
+            b"glowenft",
+            nft_name.as_bytes(),
+            b"mint",
+            program_id.as_ref(),
+            token_program_id.as_ref(),
+            payer.as_ref(),
+        ],
+        program_id,
     )
-    .0
 }
 
-pub(crate) fn derive_token_account_internal(
+/// Retrieve the mint account, as derived by the canonical deployment
+pub fn get_mint_account(minter: &Pubkey, nft_name: &str) -> Pubkey {
+    derive_mint_account(&crate::id(), &spl_token::id(), minter, nft_name).0
+}
+
+/// Derive the account holding an NFT for the given program, returning the bump seed too.
+/// `payer` is the minter of the NFT, or the drop PDA for drop items
+pub fn derive_token_account(
     program_id: &Pubkey,
     token_program_id: &Pubkey,
     payer: &Pubkey,
     nft_name: &str,
     owner: &Pubkey,
 ) -> (Pubkey, u8) {
+    let name_seed = hash_nft_name(nft_name);
     Pubkey::find_program_address(
-        &derive_token_account_seeds(program_id, token_program_id, payer, nft_name, owner),
+        &derive_token_account_seeds(program_id, token_program_id, payer, &name_seed, owner),
         program_id,
     )
 }
@@ -97,12 +486,13 @@ pub(crate) fn derive_token_account_seeds<'a>(
     program_id: &'a Pubkey,
     token_program_id: &'a Pubkey,
     payer: &'a Pubkey,
-    nft_name: &'a str,
+    name_seed: &'a [u8],
     owner: &'a Pubkey,
-) -> [&'a [u8]; 7] {
+) -> [&'a [u8]; 8] {
     [
         b"glowenft",
-        nft_name.as_bytes(),
+        SEED_VERSION,
+        name_seed,
         b"owner",
         program_id.as_ref(),
         token_program_id.as_ref(),
@@ -111,87 +501,1519 @@ pub(crate) fn derive_token_account_seeds<'a>(
     ]
 }
 
-/// Retrieve the mint account
+/// Derive the account holding an NFT minted before `SEED_VERSION` was introduced
+///
+/// Returns `None` if `nft_name` is too long to be used as a seed
+pub fn derive_legacy_token_account(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    payer: &Pubkey,
+    nft_name: &str,
+    owner: &Pubkey,
+) -> Option<(Pubkey, u8)> {
+    Pubkey::try_find_program_address(
+        &[
+            b"glowenft",
+            nft_name.as_bytes(),
+            b"owner",
+            program_id.as_ref(),
+            token_program_id.as_ref(),
+            payer.as_ref(),
+            owner.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+/// Retrieve the token account, as derived by the canonical deployment
 pub fn get_token_account(owner: &Pubkey, minter: &Pubkey, nft_name: &str) -> Pubkey {
-    derive_token_account_internal(
-        &Pubkey::new_from_array([42; 32]),
-        &spl_token::id(),
-        minter,
-        nft_name,
-        owner,
+    derive_token_account(&crate::id(), &spl_token::id(), minter, nft_name, owner).0
+}
+
+/// Derive the metadata account of the NFT minted with `mint` for the given program,
+/// returning the bump seed too
+pub fn derive_metadata_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&derive_metadata_account_seeds(program_id, mint), program_id)
+}
+
+pub(crate) fn derive_metadata_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    mint: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [b"glowenft", b"metadata", program_id.as_ref(), mint.as_ref()]
+}
+
+/// Retrieve the metadata account of the NFT minted with `mint`,
+/// as derived by the canonical deployment
+pub fn get_metadata_account(mint: &Pubkey) -> Pubkey {
+    derive_metadata_account(&crate::id(), mint).0
+}
+
+/// Derive the collection account for the given program, returning the bump seed too
+pub fn derive_collection_account(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    collection_name: &str,
+) -> (Pubkey, u8) {
+    let name_seed = hash_nft_name(collection_name);
+    Pubkey::find_program_address(
+        &derive_collection_account_seeds(program_id, authority, &name_seed),
+        program_id,
     )
-    .0
 }
 
-/// Create a new `Mint` instruction
-///
-/// `program_id` should be this program's id
-/// `name` is the name of the NFT
-/// `url` is the associated URL
-/// `payer` is the account that will be signing and paying fees
-/// `owner` is the account that will own the minted NFT at the end, usually matches `payer`
-pub fn mint(
+pub(crate) fn derive_collection_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    authority: &'a Pubkey,
+    name_seed: &'a [u8],
+) -> [&'a [u8]; 6] {
+    [
+        b"glowenft",
+        SEED_VERSION,
+        name_seed,
+        b"collection",
+        program_id.as_ref(),
+        authority.as_ref(),
+    ]
+}
+
+/// Retrieve the collection account, as derived by the canonical deployment
+pub fn get_collection_account(authority: &Pubkey, collection_name: &str) -> Pubkey {
+    derive_collection_account(&crate::id(), authority, collection_name).0
+}
+
+/// Derive the drop account for the given program, returning the bump seed too
+pub fn derive_drop_account(
     program_id: &Pubkey,
-    name: &str,
-    url: &str,
-    payer: &Pubkey,
-    owner: &Pubkey,
-) -> Result<Instruction, ProgramError> {
-    let data = GloweInstruction::Mint {
-        name: name.to_string(),
-        url: url.to_string(),
-    };
-    let data = data.try_to_vec().expect("serializing instruction failed");
+    authority: &Pubkey,
+    drop_name: &str,
+) -> (Pubkey, u8) {
+    let name_seed = hash_nft_name(drop_name);
+    Pubkey::find_program_address(
+        &derive_drop_account_seeds(program_id, authority, &name_seed),
+        program_id,
+    )
+}
 
-    Ok(Instruction {
-        program_id: *program_id,
-        accounts: vec![
-            AccountMeta::new(*payer, true),
-            AccountMeta::new_readonly(*owner, false),
-            AccountMeta::new(get_mint_account(payer, name), false),
-            AccountMeta::new(get_token_account(owner, payer, name), false),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
-        ],
-        data,
-    })
+pub(crate) fn derive_drop_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    authority: &'a Pubkey,
+    name_seed: &'a [u8],
+) -> [&'a [u8]; 6] {
+    [
+        b"glowenft",
+        SEED_VERSION,
+        name_seed,
+        b"drop",
+        program_id.as_ref(),
+        authority.as_ref(),
+    ]
 }
 
-/// Create a new `Mint2` instruction
-///
-/// `program_id` should be this program's id
-/// `name` is the name of the NFT
-/// `url` is the associated URL
-/// `payer` is the account that will be signing and paying fees
-/// `owner` is the account that will own the minted NFT at the end, usually matches `payer`
-/// `mint` is the account to be used for minting
-/// `token_holder` is the account to be used to hold the minted tokens
-pub fn mint2(
+/// Retrieve the drop account, as derived by the canonical deployment
+pub fn get_drop_account(authority: &Pubkey, drop_name: &str) -> Pubkey {
+    derive_drop_account(&crate::id(), authority, drop_name).0
+}
+
+/// Derive the redemption account of `minter` for the allowlist phase `phase` of `drop`,
+/// returning the bump seed too
+pub fn derive_redemption_account(
     program_id: &Pubkey,
-    name: &str,
-    url: &str,
-    payer: &Pubkey,
-    owner: &Pubkey,
+    drop: &Pubkey,
+    minter: &Pubkey,
+    phase: u8,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &derive_redemption_account_seeds(program_id, drop, minter, &[phase]),
+        program_id,
+    )
+}
+
+pub(crate) fn derive_redemption_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    drop: &'a Pubkey,
+    minter: &'a Pubkey,
+    phase: &'a [u8],
+) -> [&'a [u8]; 6] {
+    [
+        b"glowenft",
+        b"redemption",
+        program_id.as_ref(),
+        drop.as_ref(),
+        minter.as_ref(),
+        phase,
+    ]
+}
+
+/// Derive the account counting the items of `drop` minted by `minter`,
+/// returning the bump seed too
+pub fn derive_mint_counter_account(
+    program_id: &Pubkey,
+    drop: &Pubkey,
+    minter: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &derive_mint_counter_account_seeds(program_id, drop, minter),
+        program_id,
+    )
+}
+
+pub(crate) fn derive_mint_counter_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    drop: &'a Pubkey,
+    minter: &'a Pubkey,
+) -> [&'a [u8]; 5] {
+    [
+        b"glowenft",
+        b"counter",
+        program_id.as_ref(),
+        drop.as_ref(),
+        minter.as_ref(),
+    ]
+}
+
+/// Derive the record of the NFT of mint `mint` used to pass the token gate of `drop`,
+/// returning the bump seed too
+pub fn derive_gate_record_account(
+    program_id: &Pubkey,
+    drop: &Pubkey,
     mint: &Pubkey,
-    token_holder: &Pubkey,
-) -> Result<Instruction, ProgramError> {
-    let data = GloweInstruction::Mint2 {
-        name: name.to_string(),
-        url: url.to_string(),
-    };
-    let data = data.try_to_vec().expect("serializing instruction failed");
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &derive_gate_record_account_seeds(program_id, drop, mint),
+        program_id,
+    )
+}
 
-    Ok(Instruction {
-        program_id: *program_id,
-        accounts: vec![
-            AccountMeta::new(*payer, true),
-            AccountMeta::new_readonly(*owner, false),
-            AccountMeta::new(*mint, false),
-            AccountMeta::new(*token_holder, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
-            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
-        ],
+pub(crate) fn derive_gate_record_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    drop: &'a Pubkey,
+    mint: &'a Pubkey,
+) -> [&'a [u8]; 5] {
+    [
+        b"glowenft",
+        b"gate",
+        program_id.as_ref(),
+        drop.as_ref(),
+        mint.as_ref(),
+    ]
+}
+
+/// Derive the record of the normalized NFT name `nft_name` for the given program,
+/// shared by all minters, returning the bump seed too
+pub fn derive_name_record_account(program_id: &Pubkey, nft_name: &str) -> (Pubkey, u8) {
+    let name_seed = hash_nft_name(nft_name);
+    Pubkey::find_program_address(
+        &derive_name_record_account_seeds(program_id, &name_seed),
+        program_id,
+    )
+}
+
+pub(crate) fn derive_name_record_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    name_seed: &'a [u8],
+) -> [&'a [u8]; 5] {
+    [
+        b"glowenft",
+        SEED_VERSION,
+        name_seed,
+        b"name",
+        program_id.as_ref(),
+    ]
+}
+
+/// Retrieve the name record account, as derived by the canonical deployment.
+/// The name is registered if the account exists
+pub fn get_name_record_account(nft_name: &str) -> Pubkey {
+    derive_name_record_account(&crate::id(), nft_name).0
+}
+
+/// Derive the config account of the given program, returning the bump seed too
+pub fn derive_config_account(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&derive_config_account_seeds(program_id), program_id)
+}
+
+pub(crate) fn derive_config_account_seeds(program_id: &Pubkey) -> [&[u8]; 3] {
+    [b"glowenft", b"config", program_id.as_ref()]
+}
+
+/// Derive the treasury account of the given program, returning the bump seed too
+pub fn derive_treasury_account(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&derive_treasury_account_seeds(program_id), program_id)
+}
+
+pub(crate) fn derive_treasury_account_seeds(program_id: &Pubkey) -> [&[u8]; 3] {
+    [b"glowenft", b"treasury", program_id.as_ref()]
+}
+
+/// Derive the program data account of the given program, deployed with the upgradeable
+/// BPF loader, that records its upgrade authority
+pub fn derive_program_data_account(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &solana_program::bpf_loader_upgradeable::id(),
+    )
+    .0
+}
+
+/// Retrieve the config account, as derived by the canonical deployment
+pub fn get_config_account() -> Pubkey {
+    derive_config_account(&crate::id()).0
+}
+
+/// Derive the master edition account of the NFT minted with `mint` for the given program,
+/// returning the bump seed too
+pub fn derive_master_edition_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &derive_master_edition_account_seeds(program_id, mint),
+        program_id,
+    )
+}
+
+pub(crate) fn derive_master_edition_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    mint: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [b"glowenft", b"edition", program_id.as_ref(), mint.as_ref()]
+}
+
+/// Derive the edition marker account of the master edition minted with `mint`
+/// for the given program, returning the bump seed too
+pub fn derive_edition_marker_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &derive_edition_marker_account_seeds(program_id, mint),
+        program_id,
+    )
+}
+
+pub(crate) fn derive_edition_marker_account_seeds<'a>(
+    program_id: &'a Pubkey,
+    mint: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [
+        b"glowenft",
+        b"edition_marker",
+        program_id.as_ref(),
+        mint.as_ref(),
+    ]
+}
+
+/// Derive the freeze authority of the NFT minted with `mint` for the given program,
+/// returning the bump seed too
+pub fn derive_freeze_authority(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&derive_freeze_authority_seeds(program_id, mint), program_id)
+}
+
+pub(crate) fn derive_freeze_authority_seeds<'a>(
+    program_id: &'a Pubkey,
+    mint: &'a Pubkey,
+) -> [&'a [u8]; 4] {
+    [b"glowenft", b"freeze", program_id.as_ref(), mint.as_ref()]
+}
+
+/// Name of the print `edition` of the master edition named `master_name`,
+/// used to derive the print accounts
+pub fn print_edition_name(master_name: &str, edition: u64) -> String {
+    format!("{} #{}", master_name, edition)
+}
+
+/// Name and URL of the item `number` of the drop, starting at 1,
+/// or `None` past the supply of the drop
+pub fn drop_item(drop: &NftDrop, number: u64) -> Option<(String, String)> {
+    if number == 0 || number > drop.settings.max_supply {
+        return None;
+    }
+
+    match &drop.settings.items {
+        DropItems::List(items) => items.get((number - 1) as usize).cloned(),
+        DropItems::Template(url) => Some((
+            format!("{} #{}", drop.name, number),
+            url.replace(DROP_URL_PLACEHOLDER, &number.to_string()),
+        )),
+    }
+}
+
+/// Create a new `Mint` instruction
+///
+/// `program_id` should be this program's id
+/// `name` is the name of the NFT
+/// `url` is the associated URL
+/// `payer` is the account that will be signing and paying fees
+/// `owner` is the account that will own the minted NFT at the end, usually matches `payer`
+/// `options` are the optional settings of the NFT
+pub fn mint(
+    program_id: &Pubkey,
+    name: &str,
+    url: &str,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    options: &MintOptions,
+) -> Result<Instruction, ProgramError> {
+    mint_with_token_program(
+        program_id,
+        &spl_token::id(),
+        name,
+        url,
+        payer,
+        owner,
+        options,
+    )
+}
+
+/// Create a new `Mint` instruction using the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `mint`
+pub fn mint_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    name: &str,
+    url: &str,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    options: &MintOptions,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Mint {
+        name: name.to_string(),
+        url: url.to_string(),
+        options: options.clone(),
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    let (mint, _) = derive_mint_account(program_id, token_program_id, payer, name);
+    let token_account = if options.associated_token_account {
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            owner,
+            &mint,
+            token_program_id,
+        )
+    } else {
+        derive_token_account(program_id, token_program_id, payer, name, owner).0
+    };
+    let (metadata_account, _) = derive_metadata_account(program_id, &mint);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new(metadata_account, false),
+        ]
+        .into_iter()
+        .chain(master_edition_accounts(program_id, &mint, options))
+        .chain(associated_token_accounts(options))
+        .chain(name_record_accounts(program_id, name, options))
+        .chain(creator_cosigners(payer, options))
+        .chain(std::iter::once(treasury_account(program_id)))
+        .chain(std::iter::once(config_account(program_id)))
+        .collect(),
+        data,
+    })
+}
+
+/// Create a new `Mint` instruction minting to the associated token account of `owner`
+///
+/// Arguments are the same as `mint`, `options.associated_token_account` is always set
+pub fn mint_to_ata(
+    program_id: &Pubkey,
+    name: &str,
+    url: &str,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    options: &MintOptions,
+) -> Result<Instruction, ProgramError> {
+    let options = MintOptions {
+        associated_token_account: true,
+        ..options.clone()
+    };
+    mint(program_id, name, url, payer, owner, &options)
+}
+
+/// Create a new `Mint2` instruction
+///
+/// `program_id` should be this program's id
+/// `name` is the name of the NFT
+/// `url` is the associated URL
+/// `payer` is the account that will be signing and paying fees
+/// `owner` is the account that will own the minted NFT at the end, usually matches `payer`
+/// `mint` is the account to be used for minting
+/// `token_holder` is the account to be used to hold the minted tokens
+/// `options` are the optional settings of the NFT
+#[allow(clippy::too_many_arguments)]
+pub fn mint2(
+    program_id: &Pubkey,
+    name: &str,
+    url: &str,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    options: &MintOptions,
+) -> Result<Instruction, ProgramError> {
+    mint2_with_token_program(
+        program_id,
+        &spl_token::id(),
+        name,
+        url,
+        payer,
+        owner,
+        mint,
+        token_holder,
+        options,
+    )
+}
+
+/// Create a new `Mint2` instruction using the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `mint2`
+#[allow(clippy::too_many_arguments)]
+pub fn mint2_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    name: &str,
+    url: &str,
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    options: &MintOptions,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Mint2 {
+        name: name.to_string(),
+        url: url.to_string(),
+        options: options.clone(),
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*token_holder, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ]
+        .into_iter()
+        .chain(master_edition_accounts(program_id, mint, options))
+        .chain(name_record_accounts(program_id, name, options))
+        .chain(creator_cosigners(payer, options))
+        .chain(std::iter::once(treasury_account(program_id)))
+        .chain(std::iter::once(config_account(program_id)))
+        .collect(),
+        data,
+    })
+}
+
+//master editions need their master edition and edition marker accounts
+fn master_edition_accounts(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    options: &MintOptions,
+) -> Vec<AccountMeta> {
+    match options.max_editions {
+        Some(_) => vec![
+            AccountMeta::new(derive_master_edition_account(program_id, mint).0, false),
+            AccountMeta::new(derive_edition_marker_account(program_id, mint).0, false),
+        ],
+        None => vec![],
+    }
+}
+
+//the associated token account program creates the token account of the owner
+fn associated_token_accounts(options: &MintOptions) -> Vec<AccountMeta> {
+    if options.associated_token_account {
+        vec![AccountMeta::new_readonly(
+            spl_associated_token_account::id(),
+            false,
+        )]
+    } else {
+        vec![]
+    }
+}
+
+//every minted name is checked against its name record, created when registering it
+fn name_record_accounts(
+    program_id: &Pubkey,
+    name: &str,
+    options: &MintOptions,
+) -> Vec<AccountMeta> {
+    let (name_record, _) = derive_name_record_account(program_id, name);
+    if options.register_name {
+        vec![AccountMeta::new(name_record, false)]
+    } else {
+        vec![AccountMeta::new_readonly(name_record, false)]
+    }
+}
+
+//every instruction but the config ones ends with the config account
+fn config_account(program_id: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(derive_config_account(program_id).0, false)
+}
+
+//minting instructions pay the mint fee to the treasury
+fn treasury_account(program_id: &Pubkey) -> AccountMeta {
+    AccountMeta::new(derive_treasury_account(program_id).0, false)
+}
+
+//creators marked as verified other than the payer need to co-sign the mint
+fn creator_cosigners<'a>(
+    payer: &'a Pubkey,
+    options: &'a MintOptions,
+) -> impl Iterator<Item = AccountMeta> + 'a {
+    options
+        .creators
+        .iter()
+        .filter(move |c| c.verified && &c.address != payer)
+        .map(|c| AccountMeta::new_readonly(c.address, true))
+}
+
+/// Create a new `Transfer` instruction
+///
+/// `program_id` should be this program's id
+/// `name` is the name of the NFT
+/// `minter` is the account that minted the NFT
+/// `owner` is the current owner of the NFT, that will be signing and paying fees
+/// `new_owner` is the account that will own the NFT at the end
+pub fn transfer(
+    program_id: &Pubkey,
+    name: &str,
+    minter: &Pubkey,
+    owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    transfer_with_token_program(program_id, &spl_token::id(), name, minter, owner, new_owner)
+}
+
+/// Create a new `Transfer` instruction for an NFT minted with the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `transfer`
+pub fn transfer_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    name: &str,
+    minter: &Pubkey,
+    owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Transfer;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    let (mint, _) = derive_mint_account(program_id, token_program_id, minter, name);
+    let (metadata_account, _) = derive_metadata_account(program_id, &mint);
+    let (source, _) = derive_token_account(program_id, token_program_id, minter, name, owner);
+    let (destination, _) =
+        derive_token_account(program_id, token_program_id, minter, name, new_owner);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*new_owner, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(metadata_account, false),
+            AccountMeta::new(source, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `Burn` instruction
+///
+/// `program_id` should be this program's id
+/// `mint` is the mint of the NFT
+/// `token_holder` is the account holding the NFT
+/// `owner` is the current owner of the NFT, that will be signing
+/// `recipient` is the account that will receive the lamports of the closed accounts
+pub fn burn(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    owner: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    burn_with_token_program(
+        program_id,
+        &spl_token::id(),
+        mint,
+        token_holder,
+        owner,
+        recipient,
+    )
+}
+
+/// Create a new `Burn` instruction for an NFT minted with the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `burn`
+pub fn burn_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    owner: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Burn;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            AccountMeta::new(*token_holder, false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new(derive_master_edition_account(program_id, mint).0, false),
+            AccountMeta::new(derive_edition_marker_account(program_id, mint).0, false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `CreateCollection` instruction
+///
+/// `program_id` should be this program's id
+/// `name` is the name of the collection
+/// `url` is the associated URL
+/// `authority` is the authority of the collection, that will be signing and paying fees
+pub fn create_collection(
+    program_id: &Pubkey,
+    name: &str,
+    url: &str,
+    authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::CreateCollection {
+        name: name.to_string(),
+        url: url.to_string(),
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(
+                derive_collection_account(program_id, authority, name).0,
+                false,
+            ),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `VerifyCollectionItem` instruction
+///
+/// `program_id` should be this program's id
+/// `collection` is the collection account
+/// `authority` is the authority of the collection, that will be signing
+/// `mint` is the mint of the NFT to verify
+pub fn verify_collection_item(
+    program_id: &Pubkey,
+    collection: &Pubkey,
+    authority: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::VerifyCollectionItem;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*collection, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `SignCreator` instruction
+///
+/// `program_id` should be this program's id
+/// `creator` is the creator of the NFT, that will be signing
+/// `mint` is the mint of the NFT
+pub fn sign_creator(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::SignCreator;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*creator, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `PrintEdition` instruction
+///
+/// `program_id` should be this program's id
+/// `master_name` is the name of the master edition
+/// `master_mint` is the mint of the master edition
+/// `master_token_holder` is the account holding the master edition
+/// `master_owner` is the owner of the master edition, that will be signing and paying fees
+/// `owner` is the account that will own the print at the end, usually matches `master_owner`
+/// `edition` is the number of the edition to print
+pub fn print_edition(
+    program_id: &Pubkey,
+    master_name: &str,
+    master_mint: &Pubkey,
+    master_token_holder: &Pubkey,
+    master_owner: &Pubkey,
+    owner: &Pubkey,
+    edition: u64,
+) -> Result<Instruction, ProgramError> {
+    print_edition_with_token_program(
+        program_id,
+        &spl_token::id(),
+        master_name,
+        master_mint,
+        master_token_holder,
+        master_owner,
+        owner,
+        edition,
+    )
+}
+
+/// Create a new `PrintEdition` instruction for a master edition minted with the given
+/// token program, the print is minted with the same token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `print_edition`
+#[allow(clippy::too_many_arguments)]
+pub fn print_edition_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    master_name: &str,
+    master_mint: &Pubkey,
+    master_token_holder: &Pubkey,
+    master_owner: &Pubkey,
+    owner: &Pubkey,
+    edition: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::PrintEdition { edition };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    let name = print_edition_name(master_name, edition);
+    let (mint, _) = derive_mint_account(program_id, token_program_id, master_owner, &name);
+    let (token_account, _) =
+        derive_token_account(program_id, token_program_id, master_owner, &name, owner);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*master_owner, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*master_mint, false),
+            AccountMeta::new_readonly(derive_metadata_account(program_id, master_mint).0, false),
+            AccountMeta::new(
+                derive_master_edition_account(program_id, master_mint).0,
+                false,
+            ),
+            AccountMeta::new(
+                derive_edition_marker_account(program_id, master_mint).0,
+                false,
+            ),
+            AccountMeta::new_readonly(*master_token_holder, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new(derive_metadata_account(program_id, &mint).0, false),
+            AccountMeta::new_readonly(derive_name_record_account(program_id, &name).0, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            treasury_account(program_id),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `UpdateMetadata` instruction
+///
+/// `program_id` should be this program's id
+/// `mint` is the mint of the NFT
+/// `update_authority` is the update authority of the NFT, that will be signing and paying fees
+/// `display_name`, `url`, `new_update_authority` and `is_mutable` are the fields to update, if any
+pub fn update_metadata(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    update_authority: &Pubkey,
+    display_name: Option<&str>,
+    url: Option<&str>,
+    new_update_authority: Option<&Pubkey>,
+    is_mutable: Option<bool>,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::UpdateMetadata {
+        display_name: display_name.map(str::to_string),
+        url: url.map(str::to_string),
+        update_authority: new_update_authority.copied(),
+        is_mutable,
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*update_authority, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `Freeze` instruction
+///
+/// `program_id` should be this program's id
+/// `mint` is the mint of the NFT
+/// `token_holder` is the account holding the NFT
+/// `delegate` is the freeze delegate of the NFT, that will be signing
+pub fn freeze(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    freeze_with_token_program(program_id, &spl_token::id(), mint, token_holder, delegate)
+}
+
+/// Create a new `Freeze` instruction for an NFT minted with the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `freeze`
+pub fn freeze_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    freeze_instruction(
+        program_id,
+        token_program_id,
+        GloweInstruction::Freeze,
+        mint,
+        token_holder,
+        delegate,
+    )
+}
+
+/// Create a new `Thaw` instruction
+///
+/// `program_id` should be this program's id
+/// `mint` is the mint of the NFT
+/// `token_holder` is the account holding the NFT
+/// `delegate` is the freeze delegate of the NFT, that will be signing
+pub fn thaw(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    thaw_with_token_program(program_id, &spl_token::id(), mint, token_holder, delegate)
+}
+
+/// Create a new `Thaw` instruction for an NFT minted with the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `thaw`
+pub fn thaw_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    freeze_instruction(
+        program_id,
+        token_program_id,
+        GloweInstruction::Thaw,
+        mint,
+        token_holder,
+        delegate,
+    )
+}
+
+//`Freeze` and `Thaw` expect the same accounts
+fn freeze_instruction(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    instruction: GloweInstruction,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    delegate: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = instruction
+        .try_to_vec()
+        .expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*delegate, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            AccountMeta::new(*token_holder, false),
+            AccountMeta::new_readonly(derive_freeze_authority(program_id, mint).0, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `Revoke` instruction
+///
+/// `program_id` should be this program's id
+/// `mint` is the mint of the soulbound NFT
+/// `token_holder` is the account holding the NFT
+/// `issuer` is the account that minted the NFT, that will be signing and receiving the lamports
+pub fn revoke(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    token_holder: &Pubkey,
+    issuer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Revoke;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*issuer, true),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(derive_metadata_account(program_id, mint).0, false),
+            AccountMeta::new(*token_holder, false),
+            AccountMeta::new_readonly(derive_freeze_authority(program_id, mint).0, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `MintBatch` instruction
+///
+/// `program_id` should be this program's id
+/// `items` are the names and URLs of the NFTs
+/// `payer` is the account that will be signing and paying fees
+/// `owner` is the account that will own the minted NFTs at the end, usually matches `payer`
+pub fn mint_batch(
+    program_id: &Pubkey,
+    items: &[(&str, &str)],
+    payer: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    mint_batch_with_token_program(program_id, &spl_token::id(), items, payer, owner)
+}
+
+/// Create a new `MintBatch` instruction using the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `mint_batch`
+pub fn mint_batch_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    items: &[(&str, &str)],
+    payer: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::MintBatch {
+        items: items
+            .iter()
+            .map(|(name, url)| (name.to_string(), url.to_string()))
+            .collect(),
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*owner, false),
+        AccountMeta::new_readonly(*token_program_id, false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+    ];
+    for (name, _) in items {
+        let (mint, _) = derive_mint_account(program_id, token_program_id, payer, name);
+        let (token_account, _) =
+            derive_token_account(program_id, token_program_id, payer, name, owner);
+        accounts.push(AccountMeta::new(mint, false));
+        accounts.push(AccountMeta::new(token_account, false));
+        accounts.push(AccountMeta::new(
+            derive_metadata_account(program_id, &mint).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            derive_name_record_account(program_id, name).0,
+            false,
+        ));
+    }
+
+    accounts.push(treasury_account(program_id));
+    accounts.push(config_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Create as many `MintBatch` instructions as needed to mint all the `items`,
+/// each one fitting in its own transaction signed by `payer` only
+///
+/// Arguments are the same as `mint_batch`
+pub fn mint_batches(
+    program_id: &Pubkey,
+    items: &[(&str, &str)],
+    payer: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Vec<Instruction>, ProgramError> {
+    mint_batches_with_token_program(program_id, &spl_token::id(), items, payer, owner)
+}
+
+/// Create as many `MintBatch` instructions as needed to mint all the `items` using
+/// the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `mint_batches`
+pub fn mint_batches_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    items: &[(&str, &str)],
+    payer: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Vec<Instruction>, ProgramError> {
+    //signature, message header, blockhash and the keys of the payer, owner, programs,
+    // sysvar, treasury and config, plus the instruction header
+    const BATCH_OVERHEAD: usize = 1 + 64 + 3 + 3 + 8 * 32 + 32 + 1 + 3 + 3 + 1 + 4;
+
+    //the 4 PDAs and their indexes, and the borsh-encoded name and URL
+    let item_size = |(name, url): &(&str, &str)| 4 * (32 + 1) + 4 + name.len() + 4 + url.len();
+
+    let mut batches = vec![];
+    let mut start = 0;
+    while start < items.len() {
+        let mut end = start;
+        let mut size = BATCH_OVERHEAD;
+        while end < items.len()
+            && end - start < MAX_MINT_BATCH_ITEMS
+            && size + item_size(&items[end]) <= MAX_TRANSACTION_SIZE
+        {
+            size += item_size(&items[end]);
+            end += 1;
+        }
+        if end == start {
+            //a single item too large to fit in a transaction
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        batches.push(mint_batch_with_token_program(
+            program_id,
+            token_program_id,
+            &items[start..end],
+            payer,
+            owner,
+        )?);
+        start = end;
+    }
+
+    Ok(batches)
+}
+
+/// Whether the token account with the given `owner` program and `data` can be reclaimed
+/// by `payer` with a `Reclaim` instruction: either uninitialized, in which case the token
+/// account must co-sign, or empty and owned by `payer`
+pub fn is_reclaimable(owner: &Pubkey, data: &[u8], payer: &Pubkey) -> bool {
+    if !spl_token::check_id(owner) && !spl_token_2022::check_id(owner) {
+        return false;
+    }
+
+    //Token-2022 accounts store their extensions after the base account
+    let data = match data.get(..spl_token_2022::state::Account::LEN) {
+        Some(data) => data,
+        None => return false,
+    };
+    match spl_token_2022::state::Account::unpack_unchecked(data) {
+        Ok(account) => match account.state {
+            spl_token_2022::state::AccountState::Uninitialized => true,
+            spl_token_2022::state::AccountState::Initialized => {
+                &account.owner == payer && account.amount == 0 && !account.is_native()
+            }
+            spl_token_2022::state::AccountState::Frozen => false,
+        },
+        Err(_) => false,
+    }
+}
+
+/// Create a new `Reclaim` instruction
+///
+/// `program_id` should be this program's id
+/// `payer` is the account that will be signing and receiving the lamports
+/// `token_holder` is the token account to close, signing with the keypair it was created with
+/// `mint` is the mint of the token account, or any initialized mint if uninitialized
+pub fn reclaim(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    token_holder: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    reclaim_with_token_program(program_id, &spl_token::id(), payer, token_holder, mint)
+}
+
+/// Create a new `Reclaim` instruction for a token account of the given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `reclaim`
+pub fn reclaim_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    payer: &Pubkey,
+    token_holder: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Reclaim;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*token_holder, true),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `ReleaseName` instruction
+///
+/// `program_id` should be this program's id
+/// `name` is the registered name
+/// `mint` is the mint of the burned NFT
+/// `registrant` is the account that registered the name, receiving the lamports
+pub fn release_name(
+    program_id: &Pubkey,
+    name: &str,
+    mint: &Pubkey,
+    registrant: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::ReleaseName;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*registrant, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(derive_name_record_account(program_id, name).0, false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `Initialize` instruction
+///
+/// `program_id` should be this program's id
+/// `admin` is the admin of the program, that will be signing and paying fees
+pub fn initialize(program_id: &Pubkey, admin: &Pubkey) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::Initialize;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(derive_config_account(program_id).0, false),
+            AccountMeta::new(derive_treasury_account(program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            AccountMeta::new_readonly(derive_program_data_account(program_id), false),
+        ],
+        data,
+    })
+}
+
+/// Create a new `SetAdmin` instruction
+///
+/// `program_id` should be this program's id
+/// `admin` is the current admin of the program, that will be signing
+/// `new_admin` is the account that will be the admin at the end
+pub fn set_admin(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    new_admin: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::SetAdmin { admin: *new_admin };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(derive_config_account(program_id).0, false),
+        ],
+        data,
+    })
+}
+
+/// Create a new `SetPaused` instruction
+///
+/// `program_id` should be this program's id
+/// `admin` is the admin of the program, that will be signing
+/// `paused` is whether the program is paused at the end
+pub fn set_paused(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    paused: bool,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::SetPaused { paused };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(derive_config_account(program_id).0, false),
+        ],
+        data,
+    })
+}
+
+/// Create a new `SetMintFee` instruction
+///
+/// `program_id` should be this program's id
+/// `admin` is the admin of the program, that will be signing
+/// `mint_fee` is the lamports paid to the treasury for each minted NFT
+pub fn set_mint_fee(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    mint_fee: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::SetMintFee { mint_fee };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(derive_config_account(program_id).0, false),
+        ],
+        data,
+    })
+}
+
+/// Create a new `WithdrawTreasury` instruction
+///
+/// `program_id` should be this program's id
+/// `admin` is the admin of the program, that will be signing
+/// `recipient` is the account receiving the lamports
+pub fn withdraw_treasury(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::WithdrawTreasury;
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(derive_config_account(program_id).0, false),
+            AccountMeta::new(derive_treasury_account(program_id).0, false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        ],
+        data,
+    })
+}
+
+/// Create a new `CreateDrop` instruction
+///
+/// `program_id` should be this program's id
+/// `name` is the name of the drop
+/// `authority` is the authority of the drop, that will be signing and paying fees
+/// `settings` are the price, supply, schedule and items of the drop
+pub fn create_drop(
+    program_id: &Pubkey,
+    name: &str,
+    authority: &Pubkey,
+    settings: &DropSettings,
+) -> Result<Instruction, ProgramError> {
+    let data = GloweInstruction::CreateDrop {
+        name: name.to_string(),
+        settings: settings.clone(),
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(derive_drop_account(program_id, authority, name).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            config_account(program_id),
+        ],
+        data,
+    })
+}
+
+/// Create a new `MintFromDrop` instruction, minting the next item of the drop
+///
+/// `program_id` should be this program's id
+/// `drop` is the current state of the drop
+/// `buyer` is the account that will be signing, paying and receiving the item
+/// `allowlist` is the index of the current allowlist phase and the proof that the buyer
+/// is part of it, see `AllowlistTree::proof`, if the drop isn't live yet
+/// `gate` is the token account of the buyer, the mint of the NFT presented and its
+/// token program, if the drop is token gated.
+/// Tokens paid for the item, if any, come from the associated token account of the buyer
+pub fn mint_from_drop(
+    program_id: &Pubkey,
+    drop: &NftDrop,
+    buyer: &Pubkey,
+    allowlist: Option<(u8, &[[u8; 32]])>,
+    gate: Option<(&Pubkey, &Pubkey, &Pubkey)>,
+) -> Result<Instruction, ProgramError> {
+    mint_from_drop_with_token_program(program_id, &spl_token::id(), drop, buyer, allowlist, gate)
+}
+
+/// Create a new `MintFromDrop` instruction, minting the next item of the drop with the
+/// given token program
+///
+/// `token_program_id` is either the SPL Token or the Token-2022 program id,
+/// the other arguments are the same as `mint_from_drop`
+pub fn mint_from_drop_with_token_program(
+    program_id: &Pubkey,
+    token_program_id: &Pubkey,
+    drop: &NftDrop,
+    buyer: &Pubkey,
+    allowlist: Option<(u8, &[[u8; 32]])>,
+    gate: Option<(&Pubkey, &Pubkey, &Pubkey)>,
+) -> Result<Instruction, ProgramError> {
+    let (name, _) = drop_item(drop, drop.minted + 1).ok_or(GloweError::DropSoldOut)?;
+    let (drop_account, _) = derive_drop_account(program_id, &drop.authority, &drop.name);
+
+    let data = GloweInstruction::MintFromDrop {
+        proof: allowlist
+            .map(|(_, proof)| proof.to_vec())
+            .unwrap_or_default(),
+    };
+    let data = data.try_to_vec().expect("serializing instruction failed");
+
+    //items are derived from the drop, so that the same name can be minted from other drops
+    let (mint, _) = derive_mint_account(program_id, token_program_id, &drop_account, &name);
+    let (token_account, _) =
+        derive_token_account(program_id, token_program_id, &drop_account, &name, buyer);
+
+    let mut accounts = vec![
+        AccountMeta::new(*buyer, true),
+        AccountMeta::new(drop_account, false),
+        AccountMeta::new(drop.authority, false),
+        AccountMeta::new(mint, false),
+        AccountMeta::new(token_account, false),
+        AccountMeta::new(derive_metadata_account(program_id, &mint).0, false),
+        AccountMeta::new_readonly(derive_name_record_account(program_id, &name).0, false),
+        AccountMeta::new_readonly(*token_program_id, false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+    ];
+    let (mint_counter, _) = derive_mint_counter_account(program_id, &drop_account, buyer);
+    if drop.settings.wallet_limit.is_some() {
+        accounts.push(AccountMeta::new(mint_counter, false));
+    } else {
+        accounts.push(AccountMeta::new_readonly(mint_counter, false));
+    }
+    if let Some(token_payment) = &drop.settings.token_payment {
+        accounts.push(AccountMeta::new(
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                buyer,
+                &token_payment.mint,
+                &token_payment.token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new(token_payment.treasury, false));
+        accounts.push(AccountMeta::new_readonly(token_payment.mint, false));
+        accounts.push(AccountMeta::new_readonly(
+            token_payment.token_program,
+            false,
+        ));
+    }
+    if let (Some(token_gate), Some((gate_token_account, gate_mint, gate_token_program))) =
+        (&drop.settings.token_gate, gate)
+    {
+        accounts.push(AccountMeta::new(*gate_token_account, false));
+        accounts.push(AccountMeta::new(*gate_mint, false));
+        accounts.push(AccountMeta::new_readonly(*gate_token_program, false));
+        if let GateRequirement::Collection(_) = token_gate.requirement {
+            accounts.push(AccountMeta::new_readonly(
+                derive_metadata_account(program_id, gate_mint).0,
+                false,
+            ));
+        }
+        if token_gate.action == GateAction::MarkUsed {
+            accounts.push(AccountMeta::new(
+                derive_gate_record_account(program_id, &drop_account, gate_mint).0,
+                false,
+            ));
+        }
+    }
+    if let Some((phase, _)) = allowlist {
+        accounts.push(AccountMeta::new(
+            derive_redemption_account(program_id, &drop_account, buyer, phase).0,
+            false,
+        ));
+    }
+
+    accounts.push(treasury_account(program_id));
+    accounts.push(config_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
         data,
     })
 }
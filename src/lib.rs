@@ -1,22 +1,132 @@
 #[cfg(feature = "entrypoint")]
 mod entrypoint;
 
+solana_program::declare_id!("HTe1VLaZH2GkVf4fvZXEQCSw1cPqxfeRdpxjXGYSzQiP");
+
 pub mod processor;
 
 pub mod errors;
 
 pub mod instructions;
 
+pub mod state;
+
+pub mod allowlist;
+
 #[cfg(test)]
 mod tests {
-    use solana_program::pubkey::Pubkey;
+    use assert_matches::assert_matches;
+    use solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        hash::Hash,
+        instruction::InstructionError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+    };
     use solana_program_test::*;
-    use solana_sdk::{signature::Signer, transaction::Transaction};
+    use solana_sdk::{
+        account::Account,
+        signature::{Keypair, Signer},
+        transaction::{Transaction, TransactionError},
+    };
+    use spl_token_2022::extension::{
+        immutable_owner::ImmutableOwner, metadata_pointer::MetadataPointer,
+        non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+        BaseStateWithExtensions, StateWithExtensions,
+    };
+
+    use borsh::{BorshDeserialize, BorshSerialize};
 
-    use crate::instructions as ixs;
+    use crate::{
+        allowlist::{verify_allowlist_proof, AllowlistTree},
+        errors::GloweError,
+        instructions::{self as ixs, MintOptions},
+        processor::Processor,
+        state::{
+            AccountType, AllowlistPhase, Collection, CollectionItem, Config, Creator, DropItems,
+            DropSettings, Edition, GateAction, GateRecord, GateRequirement, MasterEdition,
+            Metadata, MintCounter, NameRecord, NftDrop, Redemption, TokenGate, TokenPayment,
+            ALLOWED_URL_SCHEMES, MAX_CREATORS, MAX_EDITION_SUPPLY, MAX_NAME_LENGTH, MAX_URL_LENGTH,
+            METADATA_VERSION,
+        },
+    };
 
     const NFT_NAME: &str = "GloweNFT";
 
+    //records `upgrade_authority` as the upgrade authority of the program and funds it,
+    // as programs added with `processor!` have no program data account
+    fn add_upgrade_authority(
+        program_test: &mut ProgramTest,
+        program_id: &Pubkey,
+        upgrade_authority: &Pubkey,
+    ) {
+        let state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*upgrade_authority),
+        };
+        program_test.add_account(
+            ixs::derive_program_data_account(program_id),
+            Account {
+                lamports: 1_000_000_000,
+                data: bincode::serialize(&state).expect("serialize program data"),
+                owner: bpf_loader_upgradeable::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        program_test.add_account(
+            *upgrade_authority,
+            Account {
+                lamports: 1_000_000_000,
+                ..Account::default()
+            },
+        );
+    }
+
+    async fn start(program_id: Pubkey) -> (BanksClient, Keypair, Hash) {
+        ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        )
+        .start()
+        .await
+    }
+
+    //starts the program with its config initialized by `admin` as upgrade authority
+    async fn start_initialized(
+        program_id: Pubkey,
+        admin: &Keypair,
+    ) -> (BanksClient, Keypair, Hash) {
+        let mut program_test = ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        );
+        add_upgrade_authority(&mut program_test, &program_id, &admin.pubkey());
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let initialize_ix =
+            ixs::initialize(&program_id, &admin.pubkey()).expect("create Initialize transaction");
+        let mut transaction = Transaction::new_with_payer(&[initialize_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, admin], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Initialize transaction");
+
+        (banks_client, payer, recent_blockhash)
+    }
+
+    //checks that a transaction failed with the given error of the program
+    fn assert_glowe_error(result: Result<(), BanksClientError>, error: GloweError) {
+        assert_matches!(
+            result.map_err(|err| err.unwrap()),
+            Err(TransactionError::InstructionError(_, InstructionError::Custom(code)))
+                if code == error as u32
+        );
+    }
+
     #[tokio::test]
     async fn test_minting() {
         let program_id = Pubkey::new_from_array([42; 32]);
@@ -33,13 +143,7 @@ mod tests {
             runtime.add_account(spl_program_id, spl_program_data.into())
         }
 
-        let (mut banks_client, payer, recent_blockhash) = ProgramTest::new(
-            "glowenft",
-            program_id,
-            processor!(crate::entrypoint::process_instruction),
-        )
-        .start()
-        .await;
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
 
         let body = ixs::mint(
             &program_id,
@@ -47,6 +151,7 @@ mod tests {
             "https://glowenft.com",
             &payer.pubkey(),
             &payer.pubkey(),
+            &MintOptions::default(),
             // &mint_pda,
             // &token_account_pda,
         )
@@ -57,4 +162,2805 @@ mod tests {
 
         assert!(banks_client.process_transaction(transaction).await.is_ok())
     }
+
+    #[tokio::test]
+    async fn test_minting_stores_metadata() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let body = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(&[body], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+        assert_eq!(metadata_account.owner, program_id);
+
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert_eq!(metadata.version, METADATA_VERSION);
+        assert_eq!(metadata.mint, mint);
+        assert_eq!(metadata.minter, payer.pubkey());
+        assert_eq!(metadata.name, NFT_NAME);
+        assert_eq!(metadata.url, "https://glowenft.com");
+    }
+
+    #[tokio::test]
+    async fn test_minting_stores_creators() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let creators = vec![
+            Creator {
+                address: payer.pubkey(),
+                share: 7_500,
+                verified: true,
+            },
+            Creator {
+                address: Keypair::new().pubkey(),
+                share: 2_500,
+                verified: false,
+            },
+        ];
+
+        let body = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                creators: creators.clone(),
+                seller_fee_basis_points: 500,
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(&[body], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert_eq!(metadata.seller_fee_basis_points, 500);
+        assert_eq!(metadata.creators, creators);
+    }
+
+    #[tokio::test]
+    async fn test_minting_invalid_creators() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let creator = |share| Creator {
+            address: Keypair::new().pubkey(),
+            share,
+            verified: false,
+        };
+        let duplicate = creator(5_000);
+        let invalid_creators = [
+            vec![creator(4_000), creator(5_000)],
+            vec![duplicate.clone(), duplicate],
+            vec![creator(1_000); MAX_CREATORS + 1],
+        ];
+
+        for creators in invalid_creators {
+            let body = ixs::mint(
+                &program_id,
+                NFT_NAME,
+                "https://glowenft.com",
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &MintOptions {
+                    creators,
+                    ..MintOptions::default()
+                },
+            )
+            .expect("create Mint transaction");
+
+            let mut transaction = Transaction::new_with_payer(&[body], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            assert!(banks_client.process_transaction(transaction).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_minting_long_name() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+        let name = "A GloweNFT with a name way longer than the maximum seed length";
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let body = ixs::mint(
+            &program_id,
+            name,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(&[body], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), name);
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert_eq!(metadata.name, name);
+
+        //names are still capped by `MAX_NAME_LENGTH`
+        let too_long = "a".repeat(MAX_NAME_LENGTH + 1);
+        let body = ixs::mint(
+            &program_id,
+            &too_long,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(&[body], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        //and so are the names of the prints of a master edition
+        let longest = "a".repeat(MAX_NAME_LENGTH);
+        let body = ixs::mint(
+            &program_id,
+            &longest,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                max_editions: Some(MAX_EDITION_SUPPLY),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(&[body], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_minting_prefunded_accounts() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        //funding the mint and token PDAs of a name must not block its minting
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let fund_mint_ix =
+            solana_program::system_instruction::transfer(&payer.pubkey(), &mint, 1_000_000);
+        let fund_token_account_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &token_account,
+            1_000_000,
+        );
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(
+            &[fund_mint_ix, fund_token_account_ix, mint_ix],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let token_account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let token_account =
+            spl_token::state::Account::unpack(&token_account.data).expect("decode token account");
+        assert_eq!(token_account.mint, mint);
+        assert_eq!(token_account.amount, 1);
+    }
+
+    #[tokio::test]
+    async fn test_transfer() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+        let new_owner = Keypair::new().pubkey();
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let mint = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let transfer = ixs::transfer(
+            &program_id,
+            NFT_NAME,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &new_owner,
+        )
+        .expect("create Transfer transaction");
+
+        //funding the destination PDA must not block transfers to the new owner
+        let (destination, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &new_owner,
+        );
+        let fund_ix =
+            solana_program::system_instruction::transfer(&payer.pubkey(), &destination, 1_000_000);
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint, fund_ix, transfer], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Transfer transaction");
+
+        let (source, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        assert!(banks_client
+            .get_account(source)
+            .await
+            .expect("fetch source account")
+            .is_none());
+
+        let destination = banks_client
+            .get_account(destination)
+            .await
+            .expect("fetch destination account")
+            .expect("destination account exists");
+        let destination =
+            spl_token::state::Account::unpack(&destination.data).expect("decode token account");
+        assert_eq!(destination.owner, new_owner);
+        assert_eq!(destination.amount, 1);
+    }
+
+    #[tokio::test]
+    async fn test_burn() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let burn_ix = ixs::burn(
+            &program_id,
+            &mint,
+            &token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+        )
+        .expect("create Burn transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, burn_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Burn transaction");
+
+        for closed in [token_account, metadata_account] {
+            assert!(banks_client
+                .get_account(closed)
+                .await
+                .expect("fetch closed account")
+                .is_none());
+        }
+
+        let mint = banks_client
+            .get_account(mint)
+            .await
+            .expect("fetch mint account")
+            .expect("mint account exists");
+        let mint = spl_token::state::Mint::unpack(&mint.data).expect("decode mint");
+        assert_eq!(mint.supply, 0);
+    }
+
+    #[tokio::test]
+    async fn test_collection() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let mut program_test = ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        );
+        //an account with the layout of a collection, but not created by `CreateCollection`
+        let forged_collection = Keypair::new().pubkey();
+        let forger = Keypair::new();
+        let forged_data = Collection {
+            account_type: AccountType::Collection,
+            version: 1,
+            authority: forger.pubkey(),
+            created_slot: 0,
+            name: "GloweCollection".to_string(),
+            url: "https://glowenft.com/collection".to_string(),
+        }
+        .try_to_vec()
+        .expect("serialize collection");
+        program_test.add_account(
+            forged_collection,
+            Account {
+                lamports: 1_000_000_000,
+                data: forged_data,
+                owner: program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let (collection, _) =
+            ixs::derive_collection_account(&program_id, &payer.pubkey(), "GloweCollection");
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+
+        let create_collection_ix = ixs::create_collection(
+            &program_id,
+            "GloweCollection",
+            "https://glowenft.com/collection",
+            &payer.pubkey(),
+        )
+        .expect("create CreateCollection transaction");
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                collection: Some(collection),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let verify_ix =
+            ixs::verify_collection_item(&program_id, &collection, &payer.pubkey(), &mint)
+                .expect("create VerifyCollectionItem transaction");
+
+        let mut transaction = Transaction::new_with_payer(
+            &[create_collection_ix, mint_ix, verify_ix],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process VerifyCollectionItem transaction");
+
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert_eq!(
+            metadata.collection,
+            Some(CollectionItem {
+                key: collection,
+                verified: true
+            })
+        );
+
+        //NFTs can't be verified by a forged collection
+        let (forged_mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), "Forged");
+        let mint_ix = ixs::mint(
+            &program_id,
+            "Forged",
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                collection: Some(forged_collection),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let verify_ix = ixs::verify_collection_item(
+            &program_id,
+            &forged_collection,
+            &forger.pubkey(),
+            &forged_mint,
+        )
+        .expect("create VerifyCollectionItem transaction");
+        let mut transaction = Transaction::new_with_payer(&[verify_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &forger], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        //nor by a metadata account passed as collection
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let verify_ix =
+            ixs::verify_collection_item(&program_id, &metadata_account, &payer.pubkey(), &mint)
+                .expect("create VerifyCollectionItem transaction");
+        let mut transaction = Transaction::new_with_payer(&[verify_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_creator() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+        let cosigner = Keypair::new();
+        let artist = Keypair::new();
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                creators: vec![
+                    Creator {
+                        address: cosigner.pubkey(),
+                        share: 5_000,
+                        verified: true,
+                    },
+                    Creator {
+                        address: artist.pubkey(),
+                        share: 5_000,
+                        verified: false,
+                    },
+                ],
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let sign_ix = ixs::sign_creator(&program_id, &artist.pubkey(), &mint)
+            .expect("create SignCreator transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, sign_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &cosigner, &artist], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process SignCreator transaction");
+
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert!(metadata.creators.iter().all(|c| c.verified));
+
+        //a verified creator can sign as any account of the instruction, here the owner
+        let options = MintOptions {
+            creators: vec![Creator {
+                address: artist.pubkey(),
+                share: 10_000,
+                verified: true,
+            }],
+            ..MintOptions::default()
+        };
+        let mut mint_ix = ixs::mint(
+            &program_id,
+            "Signed by the owner",
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &artist.pubkey(),
+            &options,
+        )
+        .expect("create Mint transaction");
+        //drop the co-signer account, before the treasury and config
+        let cosigner_index = mint_ix.accounts.len() - 3;
+        assert_eq!(
+            mint_ix.accounts.remove(cosigner_index).pubkey,
+            artist.pubkey()
+        );
+        mint_ix.accounts[1].is_signer = true;
+
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &artist], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        //but must sign
+        let mut mint_ix = ixs::mint(
+            &program_id,
+            "Not signed",
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &artist.pubkey(),
+            &options,
+        )
+        .expect("create Mint transaction");
+        mint_ix.accounts.remove(cosigner_index);
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_print_edition() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (master_mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (master_token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                max_editions: Some(2),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let print_ix = ixs::print_edition(
+            &program_id,
+            NFT_NAME,
+            &master_mint,
+            &master_token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            1,
+        )
+        .expect("create PrintEdition transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, print_ix.clone()], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process PrintEdition transaction");
+
+        let (master_edition, _) = ixs::derive_master_edition_account(&program_id, &master_mint);
+        let master_edition = banks_client
+            .get_account(master_edition)
+            .await
+            .expect("fetch master edition account")
+            .expect("master edition account exists");
+        let master_edition =
+            MasterEdition::try_from_slice(&master_edition.data).expect("decode master edition");
+        assert_eq!(master_edition.max_supply, 2);
+        assert_eq!(master_edition.supply, 1);
+
+        let print_name = ixs::print_edition_name(NFT_NAME, 1);
+        let (print_mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), &print_name);
+        let (print_metadata, _) = ixs::derive_metadata_account(&program_id, &print_mint);
+        let print_metadata = banks_client
+            .get_account(print_metadata)
+            .await
+            .expect("fetch print metadata account")
+            .expect("print metadata account exists");
+        let print_metadata =
+            Metadata::try_from_slice(&print_metadata.data).expect("decode print metadata");
+        assert_eq!(print_metadata.name, print_name);
+        assert_eq!(
+            print_metadata.edition,
+            Some(Edition {
+                master_mint,
+                number: 1
+            })
+        );
+
+        //the same edition can't be printed twice
+        let recent_blockhash = banks_client
+            .get_latest_blockhash()
+            .await
+            .expect("get blockhash");
+        let mut transaction = Transaction::new_with_payer(&[print_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        //burning the master edition closes its master edition and edition marker
+        let burn_ix = ixs::burn(
+            &program_id,
+            &master_mint,
+            &master_token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+        )
+        .expect("create Burn transaction");
+        let mut transaction = Transaction::new_with_payer(&[burn_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Burn transaction");
+
+        let (master_edition, _) = ixs::derive_master_edition_account(&program_id, &master_mint);
+        let (edition_marker, _) = ixs::derive_edition_marker_account(&program_id, &master_mint);
+        for closed in [master_edition, edition_marker] {
+            assert!(banks_client
+                .get_account(closed)
+                .await
+                .expect("fetch closed account")
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_metadata() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let update_ix = ixs::update_metadata(
+            &program_id,
+            &mint,
+            &payer.pubkey(),
+            Some("Glowe NFT, fixed"),
+            Some("ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"),
+            None,
+            Some(false),
+        )
+        .expect("create UpdateMetadata transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, update_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process UpdateMetadata transaction");
+
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert_eq!(metadata.name, NFT_NAME);
+        assert_eq!(metadata.display_name, "Glowe NFT, fixed");
+        assert_eq!(
+            metadata.url,
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        );
+        assert!(!metadata.is_mutable);
+
+        //immutable metadata can't be updated anymore
+        let update_ix = ixs::update_metadata(
+            &program_id,
+            &mint,
+            &payer.pubkey(),
+            Some(NFT_NAME),
+            None,
+            None,
+            None,
+        )
+        .expect("create UpdateMetadata transaction");
+        let recent_blockhash = banks_client
+            .get_latest_blockhash()
+            .await
+            .expect("get blockhash");
+        let mut transaction = Transaction::new_with_payer(&[update_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_freeze_and_thaw() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                freeze_delegate: Some(payer.pubkey()),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let freeze_ix = ixs::freeze(&program_id, &mint, &token_account, &payer.pubkey())
+            .expect("create Freeze transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, freeze_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Freeze transaction");
+
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = spl_token::state::Account::unpack(&account.data).expect("decode account");
+        assert!(account.is_frozen());
+
+        let thaw_ix = ixs::thaw(&program_id, &mint, &token_account, &payer.pubkey())
+            .expect("create Thaw transaction");
+        let mut transaction = Transaction::new_with_payer(&[thaw_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Thaw transaction");
+
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = spl_token::state::Account::unpack(&account.data).expect("decode account");
+        assert!(!account.is_frozen());
+
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let metadata_account = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+        let metadata = Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+        assert!(!metadata.frozen);
+    }
+
+    #[tokio::test]
+    async fn test_soulbound() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let holder = Keypair::new();
+        let (mint, _) = ixs::derive_mint_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+        );
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &holder.pubkey(),
+        );
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+        let options = MintOptions {
+            soulbound: true,
+            ..MintOptions::default()
+        };
+
+        //soulbound NFTs need the extensions of Token-2022
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &holder.pubkey(),
+            &options,
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        //the issuer alone signs, the holder is never asked
+        let mint_ix = ixs::mint_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &holder.pubkey(),
+            &options,
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let mint_account = banks_client
+            .get_account(mint)
+            .await
+            .expect("fetch mint account")
+            .expect("mint account exists");
+        let mint_state =
+            StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data)
+                .expect("decode mint");
+        assert!(mint_state.get_extension::<NonTransferable>().is_ok());
+        let permanent_delegate = mint_state
+            .get_extension::<PermanentDelegate>()
+            .expect("permanent delegate");
+        assert_eq!(
+            Option::<Pubkey>::from(permanent_delegate.delegate),
+            Some(ixs::derive_freeze_authority(&program_id, &mint).0)
+        );
+
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("decode account");
+        assert!(!account.base.is_frozen());
+        assert_eq!(account.base.owner, holder.pubkey());
+        assert_eq!(account.base.amount, 1);
+
+        let metadata = banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .expect("metadata account exists");
+        let metadata = Metadata::try_from_slice(&metadata.data).expect("decode metadata");
+        assert!(metadata.soulbound);
+        assert!(!metadata.frozen);
+
+        //the holder can neither transfer nor burn the NFT
+        let transfer_ix = ixs::transfer_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            &payer.pubkey(),
+            &holder.pubkey(),
+            &payer.pubkey(),
+        )
+        .expect("create Transfer transaction");
+        let mut transaction = Transaction::new_with_payer(&[transfer_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &holder], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        let burn_ix = ixs::burn(
+            &program_id,
+            &mint,
+            &token_account,
+            &holder.pubkey(),
+            &holder.pubkey(),
+        )
+        .expect("create Burn transaction");
+        let mut transaction = Transaction::new_with_payer(&[burn_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &holder], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        //only the issuer can revoke it
+        let revoke_ix = ixs::revoke(&program_id, &mint, &token_account, &holder.pubkey())
+            .expect("create Revoke transaction");
+        let mut transaction = Transaction::new_with_payer(&[revoke_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &holder], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        let revoke_ix = ixs::revoke(&program_id, &mint, &token_account, &payer.pubkey())
+            .expect("create Revoke transaction");
+        let mut transaction = Transaction::new_with_payer(&[revoke_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Revoke transaction");
+
+        assert!(banks_client
+            .get_account(metadata_account)
+            .await
+            .expect("fetch metadata account")
+            .is_none());
+
+        //the emptied token account stays with the holder
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("decode account");
+        assert_eq!(account.base.amount, 0);
+    }
+
+    #[tokio::test]
+    async fn test_mint_batch() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let names: Vec<String> = (0..7).map(|i| format!("{} {}", NFT_NAME, i)).collect();
+        let items: Vec<(&str, &str)> = names
+            .iter()
+            .map(|name| (name.as_str(), "https://glowenft.com"))
+            .collect();
+
+        let batches = ixs::mint_batches(&program_id, &items, &payer.pubkey(), &payer.pubkey())
+            .expect("create MintBatch transactions");
+        assert_eq!(batches.len(), 2);
+
+        for batch in batches {
+            let mut transaction = Transaction::new_with_payer(&[batch], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            banks_client
+                .process_transaction(transaction)
+                .await
+                .expect("process MintBatch transaction");
+        }
+
+        for name in &names {
+            let (mint, _) =
+                ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), name);
+            let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+            let metadata_account = banks_client
+                .get_account(metadata_account)
+                .await
+                .expect("fetch metadata account")
+                .expect("metadata account exists");
+            let metadata =
+                Metadata::try_from_slice(&metadata_account.data).expect("decode metadata");
+            assert_eq!(&metadata.name, name);
+        }
+
+        //long URLs fill the transactions before the item limit
+        let url = format!("https://glowenft.com/{}", "a".repeat(100));
+        let items: Vec<(&str, &str)> = names
+            .iter()
+            .map(|name| (name.as_str(), url.as_str()))
+            .collect();
+        let batches = ixs::mint_batches(&program_id, &items, &payer.pubkey(), &payer.pubkey())
+            .expect("create MintBatch transactions");
+        assert_eq!(batches.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mint_to_ata() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let mut program_test = ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        );
+        program_test.add_program(
+            "spl_associated_token_account",
+            spl_associated_token_account::id(),
+            processor!(spl_associated_token_account::processor::process_instruction),
+        );
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        let owner = Keypair::new().pubkey();
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let token_account =
+            spl_associated_token_account::get_associated_token_address(&owner, &mint);
+
+        let mint_ix = ixs::mint_to_ata(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &owner,
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = spl_token::state::Account::unpack(&account.data).expect("decode account");
+        assert_eq!(account.owner, owner);
+        assert_eq!(account.mint, mint);
+        assert_eq!(account.amount, 1);
+    }
+
+    #[tokio::test]
+    async fn test_minting_token_2022() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (mint, _) = ixs::derive_mint_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+        );
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+
+        let mint_ix = ixs::mint_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                non_transferable: true,
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let mint_account = banks_client
+            .get_account(mint)
+            .await
+            .expect("fetch mint account")
+            .expect("mint account exists");
+        assert_eq!(mint_account.owner, spl_token_2022::id());
+        let mint_state =
+            StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data)
+                .expect("decode mint");
+        assert_eq!(mint_state.base.supply, 1);
+        let pointer = mint_state
+            .get_extension::<MetadataPointer>()
+            .expect("metadata pointer");
+        assert_eq!(
+            Option::<Pubkey>::from(pointer.metadata_address),
+            Some(metadata_account)
+        );
+        assert!(mint_state.get_extension::<NonTransferable>().is_ok());
+
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("decode account");
+        assert_eq!(account.base.amount, 1);
+        assert!(account.get_extension::<ImmutableOwner>().is_ok());
+
+        //SPL Token mints can't have extensions
+        let mint_ix = ixs::mint(
+            &program_id,
+            "Transferable",
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                non_transferable: true,
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_token_2022() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+        let new_owner = Keypair::new().pubkey();
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let mint = ixs::mint_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let transfer = ixs::transfer_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &new_owner,
+        )
+        .expect("create Transfer transaction");
+
+        let mut transaction = Transaction::new_with_payer(&[mint, transfer], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Transfer transaction");
+
+        let (source, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        assert!(banks_client
+            .get_account(source)
+            .await
+            .expect("fetch source account")
+            .is_none());
+
+        let (destination, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &new_owner,
+        );
+        let destination = banks_client
+            .get_account(destination)
+            .await
+            .expect("fetch destination account")
+            .expect("destination account exists");
+        assert_eq!(destination.owner, spl_token_2022::id());
+        let destination =
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(&destination.data)
+                .expect("decode token account");
+        assert_eq!(destination.base.owner, new_owner);
+        assert_eq!(destination.base.amount, 1);
+        assert!(destination.get_extension::<ImmutableOwner>().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_freeze_and_burn_token_2022() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (mint, _) = ixs::derive_mint_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+        );
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let (metadata_account, _) = ixs::derive_metadata_account(&program_id, &mint);
+
+        let mint_ix = ixs::mint_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                freeze_delegate: Some(payer.pubkey()),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let freeze_ix = ixs::freeze_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            &mint,
+            &token_account,
+            &payer.pubkey(),
+        )
+        .expect("create Freeze transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, freeze_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Freeze transaction");
+
+        let account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .expect("decode token account");
+        assert!(account.base.is_frozen());
+
+        let thaw_ix = ixs::thaw_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            &mint,
+            &token_account,
+            &payer.pubkey(),
+        )
+        .expect("create Thaw transaction");
+        let burn_ix = ixs::burn_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            &mint,
+            &token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+        )
+        .expect("create Burn transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[thaw_ix, burn_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Burn transaction");
+
+        for closed in [token_account, metadata_account] {
+            assert!(banks_client
+                .get_account(closed)
+                .await
+                .expect("fetch closed account")
+                .is_none());
+        }
+
+        let mint = banks_client
+            .get_account(mint)
+            .await
+            .expect("fetch mint account")
+            .expect("mint account exists");
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data)
+            .expect("decode mint");
+        assert_eq!(mint.base.supply, 0);
+    }
+
+    #[tokio::test]
+    async fn test_print_edition_token_2022() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let (master_mint, _) = ixs::derive_mint_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+        );
+        let (master_token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+
+        let mint_ix = ixs::mint_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                max_editions: Some(2),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let print_ix = ixs::print_edition_with_token_program(
+            &program_id,
+            &spl_token_2022::id(),
+            NFT_NAME,
+            &master_mint,
+            &master_token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            1,
+        )
+        .expect("create PrintEdition transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[mint_ix, print_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process PrintEdition transaction");
+
+        let print_name = ixs::print_edition_name(NFT_NAME, 1);
+        let (print_token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token_2022::id(),
+            &payer.pubkey(),
+            &print_name,
+            &payer.pubkey(),
+        );
+        let print_token_account = banks_client
+            .get_account(print_token_account)
+            .await
+            .expect("fetch print token account")
+            .expect("print token account exists");
+        assert_eq!(print_token_account.owner, spl_token_2022::id());
+        let print_token_account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+            &print_token_account.data,
+        )
+        .expect("decode print token account");
+        assert_eq!(print_token_account.base.amount, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reclaim() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        //a token account created for `Mint2` and never initialized
+        let orphan = Keypair::new();
+        let rent = banks_client.get_rent().await.expect("fetch rent");
+        let create_ix = solana_program::system_instruction::create_account(
+            &payer.pubkey(),
+            &orphan.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        );
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[create_ix, mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &orphan], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let orphan_account = banks_client
+            .get_account(orphan.pubkey())
+            .await
+            .expect("fetch orphan account")
+            .expect("orphan account exists");
+        assert!(ixs::is_reclaimable(
+            &orphan_account.owner,
+            &orphan_account.data,
+            &payer.pubkey()
+        ));
+
+        //the token account holding an NFT can't be reclaimed
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let token_account = banks_client
+            .get_account(token_account)
+            .await
+            .expect("fetch token account")
+            .expect("token account exists");
+        assert!(!ixs::is_reclaimable(
+            &token_account.owner,
+            &token_account.data,
+            &payer.pubkey()
+        ));
+
+        //a third party can't claim the lamports of the uninitialized account
+        let thief = Keypair::new();
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &thief.pubkey(),
+            1_000_000_000,
+        );
+        let mut transaction = Transaction::new_with_payer(&[fund_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process transfer transaction");
+
+        let mut steal_ix = ixs::reclaim(&program_id, &thief.pubkey(), &orphan.pubkey(), &mint)
+            .expect("create Reclaim transaction");
+        steal_ix.accounts[1].is_signer = false;
+        let mut transaction = Transaction::new_with_payer(&[steal_ix], Some(&thief.pubkey()));
+        transaction.sign(&[&thief], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        let balance_before = banks_client
+            .get_balance(payer.pubkey())
+            .await
+            .expect("fetch payer balance");
+
+        let reclaim_ix = ixs::reclaim(&program_id, &payer.pubkey(), &orphan.pubkey(), &mint)
+            .expect("create Reclaim transaction");
+        let mut transaction = Transaction::new_with_payer(&[reclaim_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &orphan], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Reclaim transaction");
+
+        assert!(banks_client
+            .get_account(orphan.pubkey())
+            .await
+            .expect("fetch closed account")
+            .is_none());
+        let balance_after = banks_client
+            .get_balance(payer.pubkey())
+            .await
+            .expect("fetch payer balance");
+        assert!(balance_after > balance_before);
+    }
+
+    #[tokio::test]
+    async fn test_name_registry() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let options = MintOptions {
+            register_name: true,
+            ..MintOptions::default()
+        };
+        let other_minter = Keypair::new();
+
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &other_minter.pubkey(),
+            1_000_000_000,
+        );
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &options,
+        )
+        .expect("create Mint transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[fund_ix, mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (name_record_account, _) = ixs::derive_name_record_account(&program_id, NFT_NAME);
+        let name_record_account = banks_client
+            .get_account(name_record_account)
+            .await
+            .expect("fetch name record account")
+            .expect("name record account exists");
+        let name_record =
+            NameRecord::try_from_slice(&name_record_account.data).expect("decode name record");
+        assert_eq!(name_record.mint, mint);
+        assert_eq!(name_record.registrant, payer.pubkey());
+
+        //the normalized name is taken for every minter
+        let other_name = format!(" {} ", NFT_NAME.to_uppercase());
+        let other_mint_ix = ixs::mint(
+            &program_id,
+            &other_name,
+            "https://glowenft.com",
+            &other_minter.pubkey(),
+            &other_minter.pubkey(),
+            &options,
+        )
+        .expect("create Mint transaction");
+        let mut transaction = Transaction::new_with_payer(
+            std::slice::from_ref(&other_mint_ix),
+            Some(&other_minter.pubkey()),
+        );
+        transaction.sign(&[&other_minter], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::NameAlreadyRegistered,
+        );
+
+        //even when minted without registering it
+        let plain_mint_ix = ixs::mint(
+            &program_id,
+            &other_name,
+            "https://glowenft.com",
+            &other_minter.pubkey(),
+            &other_minter.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[plain_mint_ix], Some(&other_minter.pubkey()));
+        transaction.sign(&[&other_minter], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::NameAlreadyRegistered,
+        );
+
+        //the name is released once the NFT is burned
+        let (token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let early_release_ix = ixs::release_name(&program_id, NFT_NAME, &mint, &payer.pubkey())
+            .expect("create ReleaseName transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[early_release_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(transaction).await.is_err());
+
+        let burn_ix = ixs::burn(
+            &program_id,
+            &mint,
+            &token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+        )
+        .expect("create Burn transaction");
+        let release_ix = ixs::release_name(&program_id, NFT_NAME, &mint, &payer.pubkey())
+            .expect("create ReleaseName transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[burn_ix, release_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process ReleaseName transaction");
+
+        let recent_blockhash = banks_client
+            .get_new_latest_blockhash(&recent_blockhash)
+            .await
+            .expect("fetch new blockhash");
+        let mut transaction =
+            Transaction::new_with_payer(&[other_mint_ix], Some(&other_minter.pubkey()));
+        transaction.sign(&[&other_minter], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+    }
+
+    #[tokio::test]
+    async fn test_name_registry_prefunded_record() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        //funding the name record PDA must not squat the name
+        let (name_record_account, _) = ixs::derive_name_record_account(&program_id, NFT_NAME);
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &name_record_account,
+            1_000_000,
+        );
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                register_name: true,
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[fund_ix, mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        let (mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let name_record_account = banks_client
+            .get_account(name_record_account)
+            .await
+            .expect("fetch name record account")
+            .expect("name record account exists");
+        assert_eq!(name_record_account.owner, program_id);
+        let name_record =
+            NameRecord::try_from_slice(&name_record_account.data).expect("decode name record");
+        assert_eq!(name_record.mint, mint);
+    }
+
+    #[tokio::test]
+    async fn test_pause() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let admin = Keypair::new();
+        let mut program_test = ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        );
+        add_upgrade_authority(&mut program_test, &program_id, &admin.pubkey());
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        //only the upgrade authority can initialize the program
+        let initialize_ix =
+            ixs::initialize(&program_id, &payer.pubkey()).expect("create Initialize transaction");
+        let mut transaction = Transaction::new_with_payer(&[initialize_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::InvalidUpgradeAuthority,
+        );
+
+        let new_admin = Keypair::new();
+        let initialize_ix =
+            ixs::initialize(&program_id, &admin.pubkey()).expect("create Initialize transaction");
+        let pause_ix = ixs::set_paused(&program_id, &admin.pubkey(), true)
+            .expect("create SetPaused transaction");
+        let set_admin_ix = ixs::set_admin(&program_id, &admin.pubkey(), &new_admin.pubkey())
+            .expect("create SetAdmin transaction");
+
+        let mut transaction = Transaction::new_with_payer(
+            &[initialize_ix, pause_ix, set_admin_ix],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &admin], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process SetPaused transaction");
+
+        let (config_account, _) = ixs::derive_config_account(&program_id);
+        let config_account = banks_client
+            .get_account(config_account)
+            .await
+            .expect("fetch config account")
+            .expect("config account exists");
+        let config = Config::try_from_slice(&config_account.data).expect("decode config");
+        assert_eq!(config.admin, new_admin.pubkey());
+        assert!(config.paused);
+
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+        let mut transaction =
+            Transaction::new_with_payer(std::slice::from_ref(&mint_ix), Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::ProgramPaused,
+        );
+
+        //only the new admin can resume the program
+        let resume_ix = ixs::set_paused(&program_id, &admin.pubkey(), false)
+            .expect("create SetPaused transaction");
+        let mut transaction = Transaction::new_with_payer(&[resume_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &admin], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::InvalidAdmin,
+        );
+
+        let resume_ix = ixs::set_paused(&program_id, &new_admin.pubkey(), false)
+            .expect("create SetPaused transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[resume_ix, mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &new_admin], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+    }
+
+    #[tokio::test]
+    async fn test_initialize_prefunded_config() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let admin = Keypair::new();
+        let mut program_test = ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        );
+        add_upgrade_authority(&mut program_test, &program_id, &admin.pubkey());
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        //anyone can send lamports to the config PDA before the program is initialized
+        let (config_account, _) = ixs::derive_config_account(&program_id);
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &config_account,
+            1_000_000,
+        );
+        let mut transaction = Transaction::new_with_payer(&[fund_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Transfer transaction");
+
+        let initialize_ix =
+            ixs::initialize(&program_id, &admin.pubkey()).expect("create Initialize transaction");
+        let pause_ix = ixs::set_paused(&program_id, &admin.pubkey(), true)
+            .expect("create SetPaused transaction");
+        let mut transaction =
+            Transaction::new_with_payer(&[initialize_ix, pause_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &admin], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Initialize transaction");
+
+        let config_account = banks_client
+            .get_account(config_account)
+            .await
+            .expect("fetch config account")
+            .expect("config account exists");
+        assert_eq!(config_account.owner, program_id);
+        let config = Config::try_from_slice(&config_account.data).expect("decode config");
+        assert_eq!(config.admin, admin.pubkey());
+        assert!(config.paused);
+    }
+
+    #[tokio::test]
+    async fn test_mint_fee() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let admin = Keypair::new();
+        let (mut banks_client, payer, recent_blockhash) =
+            start_initialized(program_id, &admin).await;
+
+        const MINT_FEE: u64 = 1_000_000;
+        let set_mint_fee_ix = ixs::set_mint_fee(&program_id, &admin.pubkey(), MINT_FEE)
+            .expect("create SetMintFee transaction");
+        let mint_ix = ixs::mint(
+            &program_id,
+            NFT_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions {
+                max_editions: Some(1),
+                ..MintOptions::default()
+            },
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[set_mint_fee_ix, mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &admin], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process Mint transaction");
+
+        //prints are minted too, so they pay the fee
+        let (master_mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), NFT_NAME);
+        let (master_token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            NFT_NAME,
+            &payer.pubkey(),
+        );
+        let print_ix = ixs::print_edition(
+            &program_id,
+            NFT_NAME,
+            &master_mint,
+            &master_token_account,
+            &payer.pubkey(),
+            &payer.pubkey(),
+            1,
+        )
+        .expect("create PrintEdition transaction");
+        let mut transaction = Transaction::new_with_payer(&[print_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process PrintEdition transaction");
+
+        let rent = banks_client.get_rent().await.expect("fetch rent");
+        let (treasury, _) = ixs::derive_treasury_account(&program_id);
+        let treasury_balance = banks_client
+            .get_balance(treasury)
+            .await
+            .expect("fetch treasury balance");
+        assert_eq!(treasury_balance, rent.minimum_balance(0) + 2 * MINT_FEE);
+
+        let recipient = Keypair::new().pubkey();
+        let withdraw_ix = ixs::withdraw_treasury(&program_id, &admin.pubkey(), &recipient)
+            .expect("create WithdrawTreasury transaction");
+        let mut transaction = Transaction::new_with_payer(&[withdraw_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &admin], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process WithdrawTreasury transaction");
+
+        let recipient_balance = banks_client
+            .get_balance(recipient)
+            .await
+            .expect("fetch recipient balance");
+        assert_eq!(recipient_balance, 2 * MINT_FEE);
+    }
+
+    #[tokio::test]
+    async fn test_drop() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        const PRICE: u64 = 1_000_000;
+        let authority = Keypair::new();
+        let settings = DropSettings {
+            price: PRICE,
+            max_supply: 2,
+            go_live_slot: None,
+            go_live_timestamp: Some(0),
+            end_timestamp: None,
+            items: DropItems::Template("https://glowenft.com/{index}.json".to_string()),
+            phases: vec![],
+            wallet_limit: None,
+            token_payment: None,
+            token_gate: None,
+        };
+        let rent = banks_client.get_rent().await.expect("fetch rent");
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &authority.pubkey(),
+            rent.minimum_balance(0) + 10_000_000,
+        );
+        let create_drop_ix =
+            ixs::create_drop(&program_id, NFT_NAME, &authority.pubkey(), &settings)
+                .expect("create CreateDrop transaction");
+
+        let mut transaction =
+            Transaction::new_with_payer(&[fund_ix, create_drop_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &authority], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process CreateDrop transaction");
+
+        let (drop_pda, _) = ixs::derive_drop_account(&program_id, &authority.pubkey(), NFT_NAME);
+        let authority_balance = banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .expect("fetch authority balance");
+
+        for number in 1..=2 {
+            let drop_account = banks_client
+                .get_account(drop_pda)
+                .await
+                .expect("get_account")
+                .expect("drop account not found");
+            let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+            assert_eq!(drop.minted, number - 1);
+
+            let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, None)
+                .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            banks_client
+                .process_transaction(transaction)
+                .await
+                .expect("process MintFromDrop transaction");
+
+            let item_name = format!("{} #{}", NFT_NAME, number);
+            let (mint, _) =
+                ixs::derive_mint_account(&program_id, &spl_token::id(), &drop_pda, &item_name);
+            let (metadata_pda, _) = ixs::derive_metadata_account(&program_id, &mint);
+            let metadata_account = banks_client
+                .get_account(metadata_pda)
+                .await
+                .expect("get_account")
+                .expect("metadata account not found");
+            let metadata =
+                Metadata::try_from_slice(&metadata_account.data).expect("deserialize metadata");
+            assert_eq!(metadata.name, item_name);
+            assert_eq!(metadata.minter, drop_pda);
+            assert_eq!(
+                metadata.url,
+                format!("https://glowenft.com/{}.json", number)
+            );
+            assert_eq!(metadata.update_authority, authority.pubkey());
+        }
+
+        let authority_balance_after = banks_client
+            .get_balance(authority.pubkey())
+            .await
+            .expect("fetch authority balance");
+        assert_eq!(authority_balance_after, authority_balance + 2 * PRICE);
+
+        let drop_account = banks_client
+            .get_account(drop_pda)
+            .await
+            .expect("get_account")
+            .expect("drop account not found");
+        let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+        assert_eq!(drop.minted, 2);
+        assert!(ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, None).is_err());
+
+        //without a wallet limit, the mints of the buyer aren't counted
+        let (mint_counter_pda, _) =
+            ixs::derive_mint_counter_account(&program_id, &drop_pda, &payer.pubkey());
+        assert!(banks_client
+            .get_account(mint_counter_pda)
+            .await
+            .expect("get_account")
+            .is_none());
+
+        //drops not live yet or already ended can't be minted from
+        for (name, go_live_slot, end_timestamp) in
+            [("Upcoming", Some(u64::MAX), None), ("Ended", None, Some(1))]
+        {
+            let settings = DropSettings {
+                go_live_slot,
+                go_live_timestamp: None,
+                end_timestamp,
+                ..settings.clone()
+            };
+            let create_drop_ix =
+                ixs::create_drop(&program_id, name, &authority.pubkey(), &settings)
+                    .expect("create CreateDrop transaction");
+            let mut transaction =
+                Transaction::new_with_payer(&[create_drop_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer, &authority], recent_blockhash);
+            banks_client
+                .process_transaction(transaction)
+                .await
+                .expect("process CreateDrop transaction");
+
+            let (drop_pda, _) = ixs::derive_drop_account(&program_id, &authority.pubkey(), name);
+            let drop_account = banks_client
+                .get_account(drop_pda)
+                .await
+                .expect("get_account")
+                .expect("drop account not found");
+            let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+            let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, None)
+                .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            assert!(banks_client.process_transaction(transaction).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_shared_item_names() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        //two drops of the same name have the same item names
+        let other_authority = Keypair::new();
+        let settings = DropSettings {
+            price: 0,
+            max_supply: 1,
+            go_live_slot: None,
+            go_live_timestamp: None,
+            end_timestamp: None,
+            items: DropItems::Template("https://glowenft.com/{index}.json".to_string()),
+            phases: vec![],
+            wallet_limit: None,
+            token_payment: None,
+            token_gate: None,
+        };
+        let item_name = format!("{} #1", NFT_NAME);
+        let rent = banks_client.get_rent().await.expect("fetch rent");
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &other_authority.pubkey(),
+            rent.minimum_balance(0) + 10_000_000,
+        );
+        let create_drop_ix = ixs::create_drop(&program_id, NFT_NAME, &payer.pubkey(), &settings)
+            .expect("create CreateDrop transaction");
+        let create_other_drop_ix =
+            ixs::create_drop(&program_id, NFT_NAME, &other_authority.pubkey(), &settings)
+                .expect("create CreateDrop transaction");
+        //the buyer already minted an NFT of the same name
+        let mint_ix = ixs::mint(
+            &program_id,
+            &item_name,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        let mut transaction = Transaction::new_with_payer(
+            &[fund_ix, create_drop_ix, create_other_drop_ix, mint_ix],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer, &other_authority], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process CreateDrop transaction");
+
+        for authority in [payer.pubkey(), other_authority.pubkey()] {
+            let (drop_pda, _) = ixs::derive_drop_account(&program_id, &authority, NFT_NAME);
+            let drop_account = banks_client
+                .get_account(drop_pda)
+                .await
+                .expect("get_account")
+                .expect("drop account not found");
+            let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+
+            let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, None)
+                .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            banks_client
+                .process_transaction(transaction)
+                .await
+                .expect("process MintFromDrop transaction");
+
+            let (mint, _) =
+                ixs::derive_mint_account(&program_id, &spl_token::id(), &drop_pda, &item_name);
+            let (token_account, _) = ixs::derive_token_account(
+                &program_id,
+                &spl_token::id(),
+                &drop_pda,
+                &item_name,
+                &payer.pubkey(),
+            );
+            let token_account = banks_client
+                .get_account(token_account)
+                .await
+                .expect("fetch token account")
+                .expect("token account exists");
+            let token_account = spl_token::state::Account::unpack(&token_account.data)
+                .expect("decode token account");
+            assert_eq!(token_account.mint, mint);
+            assert_eq!(token_account.amount, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_wallet_limit() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let settings = DropSettings {
+            price: 0,
+            max_supply: 3,
+            go_live_slot: None,
+            go_live_timestamp: None,
+            end_timestamp: None,
+            items: DropItems::Template("https://glowenft.com/{index}.json".to_string()),
+            phases: vec![],
+            wallet_limit: Some(1),
+            token_payment: None,
+            token_gate: None,
+        };
+        let create_drop_ix = ixs::create_drop(&program_id, NFT_NAME, &payer.pubkey(), &settings)
+            .expect("create CreateDrop transaction");
+
+        //funding the mint counter PDA must not lock the wallet out of the drop
+        let (drop_pda, _) = ixs::derive_drop_account(&program_id, &payer.pubkey(), NFT_NAME);
+        let (mint_counter_pda, _) =
+            ixs::derive_mint_counter_account(&program_id, &drop_pda, &payer.pubkey());
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &mint_counter_pda,
+            1_000_000,
+        );
+
+        let mut transaction =
+            Transaction::new_with_payer(&[create_drop_ix, fund_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process CreateDrop transaction");
+
+        for minted in 0..2 {
+            let drop_account = banks_client
+                .get_account(drop_pda)
+                .await
+                .expect("get_account")
+                .expect("drop account not found");
+            let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+            let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, None)
+                .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            let result = banks_client.process_transaction(transaction).await;
+            if minted == 0 {
+                result.expect("process MintFromDrop transaction");
+            } else {
+                assert_glowe_error(result, GloweError::WalletLimitReached);
+            }
+        }
+
+        let mint_counter_account = banks_client
+            .get_account(mint_counter_pda)
+            .await
+            .expect("get_account")
+            .expect("mint counter account not found");
+        let mint_counter = MintCounter::try_from_slice(&mint_counter_account.data)
+            .expect("deserialize mint counter");
+        assert_eq!(mint_counter.count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_drop_token_payment() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let mut program_test = ProgramTest::new(
+            "glowenft",
+            program_id,
+            processor!(crate::entrypoint::process_instruction),
+        );
+        program_test.add_program(
+            "spl_associated_token_account",
+            spl_associated_token_account::id(),
+            processor!(spl_associated_token_account::processor::process_instruction),
+        );
+        let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
+
+        const AMOUNT: u64 = 250;
+        let authority = Keypair::new();
+        let payment_mint = Keypair::new();
+        let buyer_tokens = spl_associated_token_account::get_associated_token_address(
+            &payer.pubkey(),
+            &payment_mint.pubkey(),
+        );
+        let treasury = spl_associated_token_account::get_associated_token_address(
+            &authority.pubkey(),
+            &payment_mint.pubkey(),
+        );
+
+        let rent = banks_client.get_rent().await.expect("fetch rent");
+        let setup_ixs = [
+            solana_program::system_instruction::transfer(
+                &payer.pubkey(),
+                &authority.pubkey(),
+                rent.minimum_balance(0) + 10_000_000,
+            ),
+            solana_program::system_instruction::create_account(
+                &payer.pubkey(),
+                &payment_mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &payment_mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                6,
+            )
+            .expect("create InitializeMint instruction"),
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &payment_mint.pubkey(),
+                &spl_token::id(),
+            ),
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer.pubkey(),
+                &authority.pubkey(),
+                &payment_mint.pubkey(),
+                &spl_token::id(),
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &payment_mint.pubkey(),
+                &buyer_tokens,
+                &payer.pubkey(),
+                &[],
+                1_000,
+            )
+            .expect("create MintTo instruction"),
+        ];
+        let mut transaction = Transaction::new_with_payer(&setup_ixs, Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &payment_mint], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process setup transaction");
+
+        let settings = DropSettings {
+            price: 0,
+            max_supply: 2,
+            go_live_slot: None,
+            go_live_timestamp: None,
+            end_timestamp: None,
+            items: DropItems::Template("https://glowenft.com/{index}.json".to_string()),
+            phases: vec![],
+            wallet_limit: None,
+            token_payment: Some(TokenPayment {
+                mint: payment_mint.pubkey(),
+                token_program: spl_token::id(),
+                amount: AMOUNT,
+                treasury,
+            }),
+            token_gate: None,
+        };
+        let create_drop_ix =
+            ixs::create_drop(&program_id, NFT_NAME, &authority.pubkey(), &settings)
+                .expect("create CreateDrop transaction");
+        let mut transaction = Transaction::new_with_payer(&[create_drop_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &authority], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process CreateDrop transaction");
+
+        let (drop_pda, _) = ixs::derive_drop_account(&program_id, &authority.pubkey(), NFT_NAME);
+        let drop_account = banks_client
+            .get_account(drop_pda)
+            .await
+            .expect("get_account")
+            .expect("drop account not found");
+        let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+        let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, None)
+            .expect("create MintFromDrop transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process MintFromDrop transaction");
+
+        for (token_account, amount) in [(buyer_tokens, 1_000 - AMOUNT), (treasury, AMOUNT)] {
+            let token_account = banks_client
+                .get_account(token_account)
+                .await
+                .expect("get_account")
+                .expect("token account not found");
+            let token_account = spl_token::state::Account::unpack(&token_account.data)
+                .expect("unpack token account");
+            assert_eq!(token_account.amount, amount);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_token_gate() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        const GATE_NAME: &str = "Gate";
+        let (gate_mint, _) =
+            ixs::derive_mint_account(&program_id, &spl_token::id(), &payer.pubkey(), GATE_NAME);
+        let (gate_token_account, _) = ixs::derive_token_account(
+            &program_id,
+            &spl_token::id(),
+            &payer.pubkey(),
+            GATE_NAME,
+            &payer.pubkey(),
+        );
+        let mint_gate_ix = ixs::mint(
+            &program_id,
+            GATE_NAME,
+            "https://glowenft.com",
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &MintOptions::default(),
+        )
+        .expect("create Mint transaction");
+
+        //the first drop marks the gate NFT as used, the second one burns it
+        let drops = [
+            (NFT_NAME, GateAction::MarkUsed),
+            ("Burned", GateAction::Burn),
+        ];
+        let mut create_drop_ixs = vec![mint_gate_ix];
+        for (name, action) in drops {
+            let settings = DropSettings {
+                price: 0,
+                max_supply: 2,
+                go_live_slot: None,
+                go_live_timestamp: None,
+                end_timestamp: None,
+                items: DropItems::Template("https://glowenft.com/{index}.json".to_string()),
+                phases: vec![],
+                wallet_limit: None,
+                token_payment: None,
+                token_gate: Some(TokenGate {
+                    requirement: GateRequirement::Mint(gate_mint),
+                    action,
+                }),
+            };
+            create_drop_ixs.push(
+                ixs::create_drop(&program_id, name, &payer.pubkey(), &settings)
+                    .expect("create CreateDrop transaction"),
+            );
+        }
+
+        //funding the gate record PDA must not lock the gate NFT out of the drop
+        let (gate_record_pda, _) = ixs::derive_gate_record_account(
+            &program_id,
+            &ixs::derive_drop_account(&program_id, &payer.pubkey(), NFT_NAME).0,
+            &gate_mint,
+        );
+        create_drop_ixs.push(solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &gate_record_pda,
+            1_000_000,
+        ));
+
+        let mut transaction = Transaction::new_with_payer(&create_drop_ixs, Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process CreateDrop transaction");
+
+        for (name, action) in drops {
+            let (drop_pda, _) = ixs::derive_drop_account(&program_id, &payer.pubkey(), name);
+            let drop_account = banks_client
+                .get_account(drop_pda)
+                .await
+                .expect("get_account")
+                .expect("drop account not found");
+            let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+
+            let gate = Some((&gate_token_account, &gate_mint, &spl_token::id()));
+            let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, gate)
+                .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            banks_client
+                .process_transaction(transaction)
+                .await
+                .expect("process MintFromDrop transaction");
+
+            //the gate NFT can't be presented again, once used or burned
+            let drop_account = banks_client
+                .get_account(drop_pda)
+                .await
+                .expect("get_account")
+                .expect("drop account not found");
+            let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+            let mint_ix = ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), None, gate)
+                .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+            transaction.sign(&[&payer], recent_blockhash);
+            let error = match action {
+                GateAction::MarkUsed => GloweError::GateNftAlreadyUsed,
+                GateAction::Keep | GateAction::Burn => GloweError::TokenGateNotPassed,
+            };
+            assert_glowe_error(banks_client.process_transaction(transaction).await, error);
+        }
+
+        //a wallet without the gate NFT can't pass the gate
+        let outsider = Keypair::new();
+        let (drop_pda, _) = ixs::derive_drop_account(&program_id, &payer.pubkey(), NFT_NAME);
+        let drop_account = banks_client
+            .get_account(drop_pda)
+            .await
+            .expect("get_account")
+            .expect("drop account not found");
+        let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+        let gate = Some((&gate_token_account, &gate_mint, &spl_token::id()));
+        let mint_ix = ixs::mint_from_drop(&program_id, &drop, &outsider.pubkey(), None, gate)
+            .expect("create MintFromDrop transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer, &outsider], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::TokenGateNotPassed,
+        );
+
+        let gate_record_account = banks_client
+            .get_account(gate_record_pda)
+            .await
+            .expect("get_account")
+            .expect("gate record account not found");
+        let gate_record =
+            GateRecord::try_from_slice(&gate_record_account.data).expect("deserialize gate record");
+        assert_eq!(gate_record.mint, gate_mint);
+
+        let gate_token_account = banks_client
+            .get_account(gate_token_account)
+            .await
+            .expect("get_account")
+            .expect("gate token account not found");
+        let gate_token_account = spl_token::state::Account::unpack(&gate_token_account.data)
+            .expect("unpack token account");
+        assert_eq!(gate_token_account.amount, 0);
+    }
+
+    #[tokio::test]
+    async fn test_drop_allowlist() {
+        let program_id = Pubkey::new_from_array([42; 32]);
+
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let outsider = Keypair::new();
+        let tree = AllowlistTree::new(&[
+            Keypair::new().pubkey(),
+            payer.pubkey(),
+            Keypair::new().pubkey(),
+        ]);
+        let settings = DropSettings {
+            price: 0,
+            max_supply: 3,
+            go_live_slot: None,
+            go_live_timestamp: Some(i64::MAX),
+            end_timestamp: None,
+            items: DropItems::Template("https://glowenft.com/{index}.json".to_string()),
+            phases: vec![AllowlistPhase {
+                start_timestamp: 0,
+                merkle_root: tree.root(),
+                limit: 1,
+            }],
+            wallet_limit: None,
+            token_payment: None,
+            token_gate: None,
+        };
+        let rent = banks_client.get_rent().await.expect("fetch rent");
+        let fund_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &outsider.pubkey(),
+            rent.minimum_balance(0) + 100_000_000,
+        );
+        let create_drop_ix = ixs::create_drop(&program_id, NFT_NAME, &payer.pubkey(), &settings)
+            .expect("create CreateDrop transaction");
+
+        //funding the redemption PDA must not lock the wallet out of the phase
+        let (drop_pda, _) = ixs::derive_drop_account(&program_id, &payer.pubkey(), NFT_NAME);
+        let (redemption_pda, _) =
+            ixs::derive_redemption_account(&program_id, &drop_pda, &payer.pubkey(), 0);
+        let fund_redemption_ix = solana_program::system_instruction::transfer(
+            &payer.pubkey(),
+            &redemption_pda,
+            1_000_000,
+        );
+
+        let mut transaction = Transaction::new_with_payer(
+            &[fund_ix, create_drop_ix, fund_redemption_ix],
+            Some(&payer.pubkey()),
+        );
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process CreateDrop transaction");
+
+        let drop_account = banks_client
+            .get_account(drop_pda)
+            .await
+            .expect("get_account")
+            .expect("drop account not found");
+        let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+
+        let proof = tree.proof(&payer.pubkey()).expect("payer is allowlisted");
+        let mint_ix =
+            ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), Some((0, &proof)), None)
+                .expect("create MintFromDrop transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        banks_client
+            .process_transaction(transaction)
+            .await
+            .expect("process MintFromDrop transaction");
+
+        let redemption_account = banks_client
+            .get_account(redemption_pda)
+            .await
+            .expect("get_account")
+            .expect("redemption account not found");
+        let redemption =
+            Redemption::try_from_slice(&redemption_account.data).expect("deserialize redemption");
+        assert_eq!(redemption.count, 1);
+
+        //the phase limit is reached
+        let drop_account = banks_client
+            .get_account(drop_pda)
+            .await
+            .expect("get_account")
+            .expect("drop account not found");
+        let drop = NftDrop::try_from_slice(&drop_account.data).expect("deserialize drop");
+        let mint_ix =
+            ixs::mint_from_drop(&program_id, &drop, &payer.pubkey(), Some((0, &proof)), None)
+                .expect("create MintFromDrop transaction");
+        let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&payer.pubkey()));
+        transaction.sign(&[&payer], recent_blockhash);
+        assert_glowe_error(
+            banks_client.process_transaction(transaction).await,
+            GloweError::AllowlistLimitReached,
+        );
+
+        //wallets outside of the allowlist can't reuse a proof, nor mint without one
+        for allowlist in [Some((0, &proof[..])), None] {
+            let mint_ix =
+                ixs::mint_from_drop(&program_id, &drop, &outsider.pubkey(), allowlist, None)
+                    .expect("create MintFromDrop transaction");
+            let mut transaction = Transaction::new_with_payer(&[mint_ix], Some(&outsider.pubkey()));
+            transaction.sign(&[&outsider], recent_blockhash);
+            assert_glowe_error(
+                banks_client.process_transaction(transaction).await,
+                GloweError::NotAllowlisted,
+            );
+        }
+    }
+
+    #[test]
+    fn test_allowlist_tree() {
+        let wallets: Vec<Pubkey> = (0..5).map(|_| Keypair::new().pubkey()).collect();
+        let tree = AllowlistTree::new(&wallets);
+        let root = tree.root();
+
+        for wallet in &wallets {
+            let proof = tree.proof(wallet).expect("wallet is allowlisted");
+            assert!(verify_allowlist_proof(&root, wallet, &proof));
+        }
+
+        let outsider = Keypair::new().pubkey();
+        assert_eq!(tree.proof(&outsider), None);
+        let proof = tree.proof(&wallets[0]).expect("wallet is allowlisted");
+        assert!(!verify_allowlist_proof(&root, &outsider, &proof));
+
+        let single = AllowlistTree::new(&wallets[..1]);
+        assert_eq!(single.proof(&wallets[0]), Some(vec![]));
+        assert!(verify_allowlist_proof(&single.root(), &wallets[0], &[]));
+    }
+
+    #[test]
+    fn test_name_validation() {
+        assert!(Processor::validate_name(&"a".repeat(MAX_NAME_LENGTH)).is_ok());
+        assert_matches!(
+            Processor::validate_name(&"a".repeat(MAX_NAME_LENGTH + 1)),
+            Err(GloweError::NameTooLong)
+        );
+    }
+
+    #[test]
+    fn test_url_validation() {
+        for url in [
+            "https://glowenft.com/nft.json",
+            "ipfs://QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG",
+            "ar://bNbA3TEQVL60xlgCcqdz4ZPHFZ711cZ3hmkpGttDt_U",
+        ] {
+            assert!(
+                Processor::validate_url(url, ALLOWED_URL_SCHEMES).is_ok(),
+                "{}",
+                url
+            );
+        }
+
+        assert_matches!(
+            Processor::validate_url("not a url", ALLOWED_URL_SCHEMES),
+            Err(GloweError::InvalidUrl)
+        );
+        assert_matches!(
+            Processor::validate_url("http://glowenft.com", ALLOWED_URL_SCHEMES),
+            Err(GloweError::UnsupportedUrlScheme)
+        );
+
+        let long_url = format!("https://glowenft.com/{}", "a".repeat(MAX_URL_LENGTH));
+        assert_matches!(
+            Processor::validate_url(&long_url, ALLOWED_URL_SCHEMES),
+            Err(GloweError::UrlTooLong)
+        );
+    }
+
+    #[test]
+    fn test_drop_items_validation() {
+        let item = |name: &str| (name.to_string(), "https://glowenft.com/1.json".to_string());
+        let settings = DropSettings {
+            price: 0,
+            max_supply: 2,
+            go_live_slot: None,
+            go_live_timestamp: None,
+            end_timestamp: None,
+            items: DropItems::List(vec![item("Glowe #1"), item("Glowe #2")]),
+            phases: vec![],
+            wallet_limit: None,
+            token_payment: None,
+            token_gate: None,
+        };
+        assert!(Processor::validate_drop_settings(&settings).is_ok());
+
+        //names differing by case or whitespace would mint to the same accounts
+        let duplicates = DropSettings {
+            items: DropItems::List(vec![item("Glowe #1"), item(" glowe #1")]),
+            ..settings
+        };
+        assert_matches!(
+            Processor::validate_drop_settings(&duplicates),
+            Err(GloweError::InvalidDrop)
+        );
+    }
+
+    #[test]
+    fn test_creators_validation() {
+        let creator = |share| Creator {
+            address: Keypair::new().pubkey(),
+            share,
+            verified: false,
+        };
+
+        assert!(Processor::validate_creators(&[], 0).is_ok());
+        assert!(Processor::validate_creators(&[creator(4_000), creator(6_000)], 1_000).is_ok());
+
+        assert_matches!(
+            Processor::validate_creators(&[], 10_001),
+            Err(GloweError::InvalidSellerFee)
+        );
+        assert_matches!(
+            Processor::validate_creators(&[creator(4_000), creator(5_000)], 0),
+            Err(GloweError::InvalidCreatorShares)
+        );
+        assert_matches!(
+            Processor::validate_creators(&vec![creator(1_000); MAX_CREATORS + 1], 0),
+            Err(GloweError::TooManyCreators)
+        );
+
+        let duplicate = creator(5_000);
+        assert_matches!(
+            Processor::validate_creators(&[duplicate.clone(), duplicate], 0),
+            Err(GloweError::DuplicateCreator)
+        );
+    }
 }
@@ -1,21 +1,68 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::UpgradeableLoaderState,
     entrypoint::ProgramResult,
+    instruction::Instruction,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::Pack,
+    program_utils::limited_deserialize,
     pubkey::Pubkey,
     rent::Rent,
-    sysvar::Sysvar,
+    sysvar::{clock::Clock, Sysvar},
 };
 
-use crate::{errors::GloweError as Error, instructions::GloweInstruction};
+use crate::{
+    errors::GloweError as Error,
+    instructions::{GloweInstruction, MintOptions},
+    state::{
+        AccountType, Collection, CollectionItem, Config, Creator, DropItems, DropSettings, Edition,
+        EditionMarker, GateAction, GateRecord, GateRequirement, MasterEdition, Metadata,
+        MintCounter, NameRecord, NftDrop, ProgramAccount, Redemption, TokenGate,
+        ALLOWED_URL_SCHEMES, COLLECTION_VERSION, CONFIG_VERSION, DROP_URL_PLACEHOLDER,
+        DROP_VERSION, GATE_RECORD_VERSION, MASTER_EDITION_VERSION, MAX_BASIS_POINTS, MAX_CREATORS,
+        MAX_DROP_PHASES, MAX_EDITION_SUPPLY, MAX_NAME_LENGTH, MAX_URL_LENGTH, METADATA_VERSION,
+        MINT_COUNTER_VERSION, NAME_RECORD_VERSION, REDEMPTION_VERSION,
+    },
+};
 
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
 
 pub struct Processor;
 
+//accounts needed to mint an NFT into the PDAs derived from its name
+struct NftAccounts<'a, 'b> {
+    //account paying and signing the minting
+    minter: &'b AccountInfo<'a>,
+    //account the mint and token account PDAs are derived from,
+    // the minter unless the NFT is minted from a drop
+    issuer: &'b Pubkey,
+    //final recipient of the NFT
+    owner: &'b AccountInfo<'a>,
+    mint_account_info: &'b AccountInfo<'a>,
+    token_account_info: &'b AccountInfo<'a>,
+    token_program: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    rent_account: &'b AccountInfo<'a>,
+    //creates the associated token account of the owner instead of a PDA, if set
+    associated_token_program: Option<&'b AccountInfo<'a>>,
+}
+
+//accounts presented to pass the token gate of a drop
+struct TokenGateAccounts<'a, 'b> {
+    //token account of the buyer holding the NFT
+    token_account: &'b AccountInfo<'a>,
+    mint: &'b AccountInfo<'a>,
+    //token program of the NFT, which may differ from the one of the drop items
+    token_program: &'b AccountInfo<'a>,
+    //metadata of the NFT, if the gate accepts a collection
+    metadata: Option<&'b AccountInfo<'a>>,
+    //record of the NFT being used, if the gate marks it as used
+    record: Option<&'b AccountInfo<'a>>,
+}
+
 impl Processor {
     pub fn process(
         program_id: &Pubkey,
@@ -25,339 +72,1134 @@ impl Processor {
         let instruction = GloweInstruction::try_from_slice(instruction_data)
             .map_err(|_| Error::InvalidInstruction)?;
 
+        //the config account comes last, except for the instructions managing it
+        let (config, accounts) = match instruction {
+            GloweInstruction::Initialize
+            | GloweInstruction::SetAdmin { .. }
+            | GloweInstruction::SetPaused { .. }
+            | GloweInstruction::SetMintFee { .. }
+            | GloweInstruction::WithdrawTreasury => (None, accounts),
+            _ => Self::check_not_paused(program_id, accounts)?,
+        };
+
         match instruction {
-            GloweInstruction::Mint { name, url } => {
+            GloweInstruction::Mint { name, url, options } => {
                 msg!("Instruction: Mint");
-                Self::process_mint(accounts, name, url, program_id)
+                let accounts = Self::pay_mint_fee(program_id, config.as_ref(), accounts, 1)?;
+                Self::process_mint(accounts, name, url, options, program_id)
             }
-            GloweInstruction::Mint2 { name, url } => {
+            GloweInstruction::Mint2 { name, url, options } => {
                 msg!("Instruction: Mint2");
-                Self::process_mint2(accounts, name, url, program_id)
+                let accounts = Self::pay_mint_fee(program_id, config.as_ref(), accounts, 1)?;
+                Self::process_mint2(accounts, name, url, options, program_id)
+            }
+            GloweInstruction::Transfer => {
+                msg!("Instruction: Transfer");
+                Self::process_transfer(accounts, program_id)
+            }
+            GloweInstruction::Burn => {
+                msg!("Instruction: Burn");
+                Self::process_burn(accounts, program_id)
+            }
+            GloweInstruction::CreateCollection { name, url } => {
+                msg!("Instruction: CreateCollection");
+                Self::process_create_collection(accounts, name, url, program_id)
+            }
+            GloweInstruction::VerifyCollectionItem => {
+                msg!("Instruction: VerifyCollectionItem");
+                Self::process_verify_collection_item(accounts, program_id)
+            }
+            GloweInstruction::SignCreator => {
+                msg!("Instruction: SignCreator");
+                Self::process_sign_creator(accounts, program_id)
+            }
+            GloweInstruction::PrintEdition { edition } => {
+                msg!("Instruction: PrintEdition");
+                let accounts = Self::pay_mint_fee(program_id, config.as_ref(), accounts, 1)?;
+                Self::process_print_edition(accounts, edition, program_id)
+            }
+            GloweInstruction::UpdateMetadata {
+                display_name,
+                url,
+                update_authority,
+                is_mutable,
+            } => {
+                msg!("Instruction: UpdateMetadata");
+                Self::process_update_metadata(
+                    accounts,
+                    display_name,
+                    url,
+                    update_authority,
+                    is_mutable,
+                    program_id,
+                )
+            }
+            GloweInstruction::Freeze => {
+                msg!("Instruction: Freeze");
+                Self::process_freeze(accounts, true, program_id)
+            }
+            GloweInstruction::Thaw => {
+                msg!("Instruction: Thaw");
+                Self::process_freeze(accounts, false, program_id)
+            }
+            GloweInstruction::Revoke => {
+                msg!("Instruction: Revoke");
+                Self::process_revoke(accounts, program_id)
+            }
+            GloweInstruction::MintBatch { items } => {
+                msg!("Instruction: MintBatch");
+                let accounts =
+                    Self::pay_mint_fee(program_id, config.as_ref(), accounts, items.len())?;
+                Self::process_mint_batch(accounts, items, program_id)
+            }
+            GloweInstruction::Reclaim => {
+                msg!("Instruction: Reclaim");
+                Self::process_reclaim(accounts)
+            }
+            GloweInstruction::ReleaseName => {
+                msg!("Instruction: ReleaseName");
+                Self::process_release_name(accounts, program_id)
+            }
+            GloweInstruction::Initialize => {
+                msg!("Instruction: Initialize");
+                Self::process_initialize(accounts, program_id)
+            }
+            GloweInstruction::SetAdmin { admin } => {
+                msg!("Instruction: SetAdmin");
+                Self::process_update_config(accounts, program_id, |config| config.admin = admin)
+            }
+            GloweInstruction::SetPaused { paused } => {
+                msg!("Instruction: SetPaused");
+                Self::process_update_config(accounts, program_id, |config| config.paused = paused)
+            }
+            GloweInstruction::SetMintFee { mint_fee } => {
+                msg!("Instruction: SetMintFee");
+                Self::process_update_config(accounts, program_id, |config| {
+                    config.mint_fee = mint_fee
+                })
+            }
+            GloweInstruction::WithdrawTreasury => {
+                msg!("Instruction: WithdrawTreasury");
+                Self::process_withdraw_treasury(accounts, program_id)
+            }
+            GloweInstruction::CreateDrop { name, settings } => {
+                msg!("Instruction: CreateDrop");
+                Self::process_create_drop(accounts, name, settings, program_id)
+            }
+            GloweInstruction::MintFromDrop { proof } => {
+                msg!("Instruction: MintFromDrop");
+                let accounts = Self::pay_mint_fee(program_id, config.as_ref(), accounts, 1)?;
+                Self::process_mint_from_drop(accounts, proof, program_id)
             }
         }
     }
 
-    //goes from minter + spl_token + received to full NFT...
-    // creates 2 accounts in the process
-    fn process_mint(
-        accounts: &[AccountInfo],
-        name: String,
-        url: String,
+    //rejects the instruction if the program is paused, returning the config if initialized
+    // and the accounts before the config account
+    fn check_not_paused<'a, 'b>(
         program_id: &Pubkey,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
+        accounts: &'b [AccountInfo<'a>],
+    ) -> Result<(Option<Config>, &'b [AccountInfo<'a>]), ProgramError> {
+        let (config_account_info, accounts) = accounts
+            .split_last()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
 
-        //The account to pay and sign the minting
-        let minter = next_account_info(account_info_iter)?;
+        let (config_pda, _) = crate::instructions::derive_config_account(program_id);
+        if &config_pda != config_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //the program can't be paused until initialized
+        if config_account_info.owner != program_id {
+            return Ok((None, accounts));
+        }
+
+        let config: Config = Self::load_account(program_id, &config_pda, config_account_info)?;
+        if config.paused {
+            return Err(Error::ProgramPaused.into());
+        }
+
+        Ok((Some(config), accounts))
+    }
+
+    //transfers the fee of `count` NFTs from the minter to the treasury, returning the accounts
+    // before the treasury account
+    fn pay_mint_fee<'a, 'b>(
+        program_id: &Pubkey,
+        config: Option<&Config>,
+        accounts: &'b [AccountInfo<'a>],
+        count: usize,
+    ) -> Result<&'b [AccountInfo<'a>], ProgramError> {
+        let (treasury, accounts) = accounts
+            .split_last()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let (treasury_pda, _) = crate::instructions::derive_treasury_account(program_id);
+        if &treasury_pda != treasury.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //no fee until the program is initialized
+        let mint_fee = match config {
+            Some(config) => (count as u64)
+                .checked_mul(config.mint_fee)
+                .ok_or(Error::MintFeeOverflow)?,
+            None => 0,
+        };
+        if mint_fee == 0 {
+            return Ok(accounts);
+        }
+
+        //minting instructions are signed by the minter first
+        let minter = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
         if !minter.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        //The final recipient of the NFT
-        let owner = next_account_info(account_info_iter)?;
+        let transfer_ix =
+            solana_program::system_instruction::transfer(minter.key, &treasury_pda, mint_fee);
 
-        //This is the mint account that will be minting the NFT
-        let mint_account_info = next_account_info(account_info_iter)?;
+        msg!("Calling the system program to pay the mint fee...");
+        invoke(&transfer_ix, &[minter.clone(), treasury.clone()])?;
 
-        //account that will hold the nft
-        let token_account_info = next_account_info(account_info_iter)?;
+        Ok(accounts)
+    }
 
-        //retrieve SPL Token Program account
-        let token_program = next_account_info(account_info_iter)?;
-        //check it's SPL token program
-        if !spl_token::check_id(token_program.key) {
+    //creates the config PDA, with the signer as admin
+    fn process_initialize(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //admin of the program, pays for the config account
+        let admin = next_account_info(account_info_iter)?;
+        if !admin.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //account to store the config
+        let config_account_info = next_account_info(account_info_iter)?;
+
+        //account collecting the mint fees
+        let treasury = next_account_info(account_info_iter)?;
+        let (treasury_pda, _) = crate::instructions::derive_treasury_account(program_id);
+        if &treasury_pda != treasury.key {
             return Err(Error::AccountMismatch.into());
         }
 
         //retrieve System Program account
         let system_program = next_account_info(account_info_iter)?;
-        //check it's the system program
         if !solana_program::system_program::check_id(system_program.key) {
             return Err(Error::AccountMismatch.into());
         }
 
-        //verify that the mint account matches the PDA for this NFT
-        let (mint_pda, mint_pda_bump_seed) = crate::instructions::derive_mint_account_internal(
-            program_id,
-            token_program.key,
-            minter.key,
-            name.as_str(),
-        );
-        if &mint_pda != mint_account_info.key {
-            return Err(Error::AccountMismatch.into());
-        }
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
 
-        //verify that the token account matches the PDA for this NFT
-        let (token_account_pda, token_account_pda_bump_seed) =
-            crate::instructions::derive_token_account_internal(
-                program_id,
-                token_program.key,
-                minter.key,
-                name.as_str(),
-                owner.key,
-            );
-        if &token_account_pda != token_account_info.key {
+        //program data account, only the upgrade authority can become admin
+        let program_data = next_account_info(account_info_iter)?;
+        Self::check_upgrade_authority(program_id, program_data, admin)?;
+
+        //verify that the config account matches the PDA for this program
+        let (config_pda, config_pda_bump_seed) =
+            crate::instructions::derive_config_account(program_id);
+        if &config_pda != config_account_info.key {
             return Err(Error::AccountMismatch.into());
         }
 
-        // create mint_seeds (for invoke_signed)
-        let mint_seeds_partial = &crate::instructions::derive_mint_account_seeds(
-            program_id,
-            &token_program.key,
-            minter.key,
-            name.as_str(),
-        )[..];
+        // create config_seeds (for invoke_signed)
+        let config_seeds_partial =
+            &crate::instructions::derive_config_account_seeds(program_id)[..];
 
-        let mut mint_seeds = [&[] as &_; 7];
-        mint_seeds[..6].copy_from_slice(&mint_seeds_partial[..]);
+        let mut config_seeds = [&[] as &_; 4];
+        config_seeds[..3].copy_from_slice(config_seeds_partial);
 
-        let mint_pda_bump_seed = [mint_pda_bump_seed];
-        mint_seeds[6] = &mint_pda_bump_seed[..];
+        let config_pda_bump_seed = [config_pda_bump_seed];
+        config_seeds[3] = &config_pda_bump_seed[..];
 
-        // create token_account_seeds (for invoke_signed)
-        let token_account_seeds_partial = &crate::instructions::derive_token_account_seeds(
+        let config = Config {
+            account_type: AccountType::Config,
+            version: CONFIG_VERSION,
+            admin: *admin.key,
+            paused: false,
+            mint_fee: 0,
+            treasury: treasury_pda,
+        };
+
+        msg!("Calling the system program to create the config account...");
+        Self::create_program_account(
             program_id,
-            &token_program.key,
-            minter.key,
-            name.as_str(),
-            owner.key,
-        )[..];
+            admin,
+            config_account_info,
+            system_program,
+            &rent,
+            &config_seeds,
+            &config.try_to_vec()?,
+        )?;
 
-        let mut token_account_seeds = [&[] as &_; 8];
-        token_account_seeds[..7].copy_from_slice(&token_account_seeds_partial[..]);
+        //FUND TREASURY
+        // so that any mint fee can be paid to it
+        let treasury_rent = rent.minimum_balance(0).saturating_sub(treasury.lamports());
+        if treasury_rent > 0 {
+            let transfer_ix = solana_program::system_instruction::transfer(
+                admin.key,
+                &treasury_pda,
+                treasury_rent,
+            );
 
-        let token_account_pda_bump_seed = [token_account_pda_bump_seed];
-        token_account_seeds[7] = &token_account_pda_bump_seed[..];
+            msg!("Calling the system program to fund the treasury...");
+            invoke(&transfer_ix, &[admin.clone(), treasury.clone()])?;
+        }
 
-        //get Rent sysvar to calculate rent stuff
-        let rent_account = next_account_info(account_info_iter)?;
-        let rent = Rent::from_account_info(&rent_account)?;
+        Ok(())
+    }
 
-        //CREATE MINT ACCOUNT
-        {
-            let mint_create_account_ix = solana_program::system_instruction::create_account(
-                minter.key,
-                &mint_pda,
-                rent.minimum_balance(spl_token::state::Mint::LEN),
-                spl_token::state::Mint::LEN as u64,
-                token_program.key,
-            );
+    //checks that `authority` is the upgrade authority recorded in the program data account
+    fn check_upgrade_authority(
+        program_id: &Pubkey,
+        program_data: &AccountInfo,
+        authority: &AccountInfo,
+    ) -> ProgramResult {
+        if &crate::instructions::derive_program_data_account(program_id) != program_data.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        if !solana_program::bpf_loader_upgradeable::check_id(program_data.owner) {
+            return Err(ProgramError::IllegalOwner);
+        }
 
-            msg!("Calling the system program to create the mint account...");
-            invoke_signed(
-                &mint_create_account_ix,
-                &[
-                    minter.clone(),
-                    mint_account_info.clone(),
-                    token_program.clone(),
-                    system_program.clone(),
-                ],
-                &[&mint_seeds],
-            )?;
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        let state: UpgradeableLoaderState =
+            limited_deserialize(&program_data.data.borrow(), metadata_len as u64)
+                .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        match state {
+            UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: Some(upgrade_authority),
+                ..
+            } if &upgrade_authority == authority.key => Ok(()),
+            _ => Err(Error::InvalidUpgradeAuthority.into()),
         }
+    }
 
-        //CREATE TOKEN ACCOUNT
-        {
-            let create_token_account_ix = solana_program::system_instruction::create_account(
-                minter.key,
-                &token_account_pda,
-                rent.minimum_balance(spl_token::state::Account::LEN),
-                spl_token::state::Account::LEN as u64,
-                token_program.key,
-            );
+    //moves the lamports of the treasury above its rent exemption, signed by the admin
+    fn process_withdraw_treasury(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
 
-            msg!("Calling the system program to create the token account...");
-            invoke_signed(
-                &create_token_account_ix,
-                &[
-                    minter.clone(),
-                    token_account_info.clone(),
-                    token_program.clone(),
-                    system_program.clone(),
-                ],
-                &[&token_account_seeds],
-            )?;
+        //admin of the program
+        let admin = next_account_info(account_info_iter)?;
+        if !admin.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
         }
 
-        //INITIALIZE MINT ACCOUNT
-        {
-            let initialize_mint_ix = spl_token::instruction::initialize_mint(
-                token_program.key,
-                &mint_pda,
-                &mint_pda,
-                None,
-                0,
-            )?;
+        //config of the program
+        let config_account_info = next_account_info(account_info_iter)?;
+        let (config_pda, _) = crate::instructions::derive_config_account(program_id);
+        let config: Config = Self::load_account(program_id, &config_pda, config_account_info)?;
+        if &config.admin != admin.key {
+            return Err(Error::InvalidAdmin.into());
+        }
 
-            msg!("Calling the token program to initialize the minting account...");
-            invoke(
-                &initialize_mint_ix,
-                &[
-                    //mint account
-                    mint_account_info.clone(),
-                    rent_account.clone(),
-                    //token program
-                    token_program.clone(),
-                ],
-            )?;
+        //account collecting the mint fees
+        let treasury = next_account_info(account_info_iter)?;
+        let (treasury_pda, treasury_pda_bump_seed) =
+            crate::instructions::derive_treasury_account(program_id);
+        if &treasury_pda != treasury.key {
+            return Err(Error::AccountMismatch.into());
         }
 
-        //INITIALIZE TOKEN ACCOUNT
-        {
-            let initialize_token_account_ix = spl_token::instruction::initialize_account(
-                token_program.key,
-                &token_account_pda,
-                &mint_pda,
-                owner.key,
-            )?;
+        //account receiving the lamports
+        let recipient = next_account_info(account_info_iter)?;
 
-            msg!("Calling the token program to initialize the token account...");
-            invoke(
-                &initialize_token_account_ix,
-                &[
-                    //account to initialize
-                    token_account_info.clone(),
-                    //mint account
-                    mint_account_info.clone(),
-                    //the account owner
-                    owner.clone(),
-                    rent_account.clone(),
-                    //token program
-                    token_program.clone(),
-                ],
-            )?;
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
         }
 
-        //MINT TO TOKEN ACCOUNT
-        {
-            let mint_to_ix = spl_token::instruction::mint_to(
-                token_program.key,
-                &mint_pda,
-                &token_account_pda,
-                &mint_pda, //mint authority
-                &[&mint_pda],
-                1,
-            )?;
+        //get Rent sysvar, the treasury keeps its rent exemption
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
 
-            msg!("Calling the token program to mint the NFT to the token account...");
-            invoke_signed(
-                &mint_to_ix,
-                &[
-                    mint_account_info.clone(),
-                    token_account_info.clone(),
-                    //minting authority
-                    mint_account_info.clone(),
-                    token_program.clone(),
-                ],
-                &[&mint_seeds[..]],
-            )?;
+        let amount = treasury.lamports().saturating_sub(rent.minimum_balance(0));
+        if amount == 0 {
+            return Ok(());
         }
 
-        //REVOKE MINT AUTHORITY
-        {
-            let remove_mint_authority_ix = spl_token::instruction::set_authority(
-                token_program.key,
-                &mint_pda,
-                None,
-                spl_token::instruction::AuthorityType::MintTokens,
-                &mint_pda,
-                &[&mint_pda],
-            )?;
+        // create treasury_seeds (for invoke_signed)
+        let treasury_seeds_partial =
+            &crate::instructions::derive_treasury_account_seeds(program_id)[..];
 
-            msg!("Calling the token program to revoke the mint authority...");
-            invoke_signed(
-                &remove_mint_authority_ix,
-                &[
-                    mint_account_info.clone(),
-                    //minting authority
-                    mint_account_info.clone(),
-                    token_program.clone(),
-                ],
-                &[&mint_seeds[..]],
-            )?;
+        let mut treasury_seeds = [&[] as &_; 4];
+        treasury_seeds[..3].copy_from_slice(treasury_seeds_partial);
+
+        let treasury_pda_bump_seed = [treasury_pda_bump_seed];
+        treasury_seeds[3] = &treasury_pda_bump_seed[..];
+
+        let transfer_ix =
+            solana_program::system_instruction::transfer(&treasury_pda, recipient.key, amount);
+
+        msg!("Calling the system program to withdraw the treasury...");
+        invoke_signed(
+            &transfer_ix,
+            &[treasury.clone(), recipient.clone(), system_program.clone()],
+            &[&treasury_seeds],
+        )
+    }
+
+    //applies `update` to the config, signed by the admin
+    fn process_update_config(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        update: impl FnOnce(&mut Config),
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //admin of the program
+        let admin = next_account_info(account_info_iter)?;
+        if !admin.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
         }
 
-        Ok(())
+        //config of the program
+        let config_account_info = next_account_info(account_info_iter)?;
+        let (config_pda, _) = crate::instructions::derive_config_account(program_id);
+        let mut config: Config = Self::load_account(program_id, &config_pda, config_account_info)?;
+        if &config.admin != admin.key {
+            return Err(Error::InvalidAdmin.into());
+        }
+
+        update(&mut config);
+        Self::store_account(&config, config_account_info)
     }
 
-    //same as above, except the 2 accounts are already created
-    fn process_mint2(
+    //goes from minter + spl_token + received to full NFT...
+    // creates 3 accounts in the process, 5 for master editions
+    fn process_mint(
         accounts: &[AccountInfo],
         name: String,
         url: String,
+        options: MintOptions,
         program_id: &Pubkey,
     ) -> ProgramResult {
         let account_info_iter = &mut accounts.iter();
 
-        //account to mint the token and pay all the fees
+        //The account to pay and sign the minting
         let minter = next_account_info(account_info_iter)?;
         if !minter.is_signer {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        //account to receive the token
-        let receiver = next_account_info(account_info_iter)?;
-
-        //account to mint the token
-        let mint = next_account_info(account_info_iter)?;
-        //account to hold the token
-        let token_account = next_account_info(account_info_iter)?;
+        Self::validate_name(&name)?;
+        Self::validate_url(&url, ALLOWED_URL_SCHEMES)?;
+        Self::validate_creators(&options.creators, options.seller_fee_basis_points)?;
+        Self::validate_max_editions(options.max_editions)?;
+        //prints are named after the master edition, the last one has the longest name
+        if let Some(max_supply) = options.max_editions {
+            Self::validate_name(&crate::instructions::print_edition_name(&name, max_supply))?;
+        }
 
-        //SPL token program
+        //The final recipient of the NFT
+        let owner = next_account_info(account_info_iter)?;
+
+        //This is the mint account that will be minting the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //account that will hold the nft
+        let token_account_info = next_account_info(account_info_iter)?;
+
+        //retrieve SPL Token Program account
         let token_program = next_account_info(account_info_iter)?;
-        if !spl_token::check_id(token_program.key) {
+        //check it's SPL token or Token-2022
+        Self::check_token_program(token_program)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        //check it's the system program
+        if !solana_program::system_program::check_id(system_program.key) {
             return Err(Error::AccountMismatch.into());
         }
-        //check that `mint` and `token_account` are SPL token accounts
-        if mint.owner != token_program.key || token_account.owner != token_program.key {
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //account to store the NFT metadata
+        let metadata_account_info = next_account_info(account_info_iter)?;
+
+        //accounts to store the master edition and its edition marker, if any
+        let master_edition_accounts = match options.max_editions {
+            Some(_) => Some((
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+            )),
+            None => None,
+        };
+
+        //associated token account program, to create the token account of the owner
+        let associated_token_program = match options.associated_token_account {
+            true => {
+                let associated_token_program = next_account_info(account_info_iter)?;
+                if !spl_associated_token_account::check_id(associated_token_program.key) {
+                    return Err(Error::AccountMismatch.into());
+                }
+                Some(associated_token_program)
+            }
+            false => None,
+        };
+
+        //name record, to register the name across all minters or check it isn't
+        let name_record_info = next_account_info(account_info_iter)?;
+
+        //verified creators sign either as one of the accounts above or as co-signers
+        Self::check_creator_signatures(&options.creators, accounts)?;
+
+        //REGISTER NAME
+        Self::check_name_available(program_id, name_record_info, &name, mint_account_info.key)?;
+        if options.register_name {
+            Self::create_name_record(
+                program_id,
+                minter,
+                name_record_info,
+                system_program,
+                &rent,
+                &name,
+                mint_account_info.key,
+            )?;
+        }
+
+        //master editions keep their mint authority through the master edition PDA
+        let master_edition_pda = options.max_editions.map(|_| {
+            crate::instructions::derive_master_edition_account(program_id, mint_account_info.key).0
+        });
+
+        //freezable NFTs have the freeze authority PDA as mint freeze authority
+        // and soulbound ones as permanent delegate, so their minter can revoke them
+        let freeze_authority_pda =
+            crate::instructions::derive_freeze_authority(program_id, mint_account_info.key).0;
+
+        Self::mint_nft(
+            program_id,
+            &NftAccounts {
+                minter,
+                issuer: minter.key,
+                owner,
+                mint_account_info,
+                token_account_info,
+                token_program,
+                system_program,
+                rent_account,
+                associated_token_program,
+            },
+            &name,
+            master_edition_pda.as_ref(),
+            options.freeze_delegate.and(Some(&freeze_authority_pda)),
+            options.non_transferable || options.soulbound,
+            options.soulbound.then_some(&freeze_authority_pda),
+        )?;
+
+        //CREATE METADATA ACCOUNT
+        Self::create_metadata_account(
+            program_id,
+            minter,
+            metadata_account_info,
+            system_program,
+            &rent,
+            &Metadata {
+                account_type: AccountType::Metadata,
+                version: METADATA_VERSION,
+                mint: *mint_account_info.key,
+                minter: *minter.key,
+                created_slot: Clock::get()?.slot,
+                display_name: name.clone(),
+                name,
+                url,
+                collection: options.collection.map(|key| CollectionItem {
+                    key,
+                    verified: false,
+                }),
+                seller_fee_basis_points: options.seller_fee_basis_points,
+                creators: options.creators,
+                edition: None,
+                update_authority: options.update_authority.unwrap_or(*minter.key),
+                is_mutable: !options.immutable,
+                freeze_delegate: options.freeze_delegate,
+                frozen: false,
+                soulbound: options.soulbound,
+            },
+        )?;
+
+        //CREATE MASTER EDITION ACCOUNTS
+        if let (Some(max_supply), Some((master_edition_account, edition_marker_account))) =
+            (options.max_editions, master_edition_accounts)
+        {
+            Self::create_master_edition(
+                program_id,
+                minter,
+                mint_account_info.key,
+                master_edition_account,
+                edition_marker_account,
+                system_program,
+                &rent,
+                max_supply,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //same as `process_mint` with the default options for each item,
+    // the program accounts are only checked once
+    fn process_mint_batch(
+        accounts: &[AccountInfo],
+        items: Vec<(String, String)>,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //The account to pay and sign the minting
+        let minter = next_account_info(account_info_iter)?;
+        if !minter.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if items.is_empty() {
+            return Err(Error::InvalidInstruction.into());
+        }
+        for (name, url) in &items {
+            Self::validate_name(name)?;
+            Self::validate_url(url, ALLOWED_URL_SCHEMES)?;
+        }
+
+        //The final recipient of the NFTs
+        let owner = next_account_info(account_info_iter)?;
+
+        //retrieve SPL Token or Token-2022 Program account
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        let created_slot = Clock::get()?.slot;
+
+        for (name, url) in items {
+            //mint, token, metadata and name record accounts of this NFT
+            let mint_account_info = next_account_info(account_info_iter)?;
+            let token_account_info = next_account_info(account_info_iter)?;
+            let metadata_account_info = next_account_info(account_info_iter)?;
+            let name_record_info = next_account_info(account_info_iter)?;
+            Self::check_name_available(program_id, name_record_info, &name, mint_account_info.key)?;
+
+            Self::mint_nft(
+                program_id,
+                &NftAccounts {
+                    minter,
+                    issuer: minter.key,
+                    owner,
+                    mint_account_info,
+                    token_account_info,
+                    token_program,
+                    system_program,
+                    rent_account,
+                    associated_token_program: None,
+                },
+                &name,
+                None,
+                None,
+                false,
+                None,
+            )?;
+
+            Self::create_metadata_account(
+                program_id,
+                minter,
+                metadata_account_info,
+                system_program,
+                &rent,
+                &Metadata {
+                    account_type: AccountType::Metadata,
+                    version: METADATA_VERSION,
+                    mint: *mint_account_info.key,
+                    minter: *minter.key,
+                    created_slot,
+                    display_name: name.clone(),
+                    name,
+                    url,
+                    collection: None,
+                    seller_fee_basis_points: 0,
+                    creators: vec![],
+                    edition: None,
+                    update_authority: *minter.key,
+                    is_mutable: true,
+                    freeze_delegate: None,
+                    frozen: false,
+                    soulbound: false,
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //creates a drop PDA owned by the signing authority
+    fn process_create_drop(
+        accounts: &[AccountInfo],
+        name: String,
+        settings: DropSettings,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //authority of the drop, pays for the drop account
+        let authority = next_account_info(account_info_iter)?;
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        Self::validate_drop_settings(&settings)?;
+        //template items are named after the drop, the last one has the longest name
+        if let DropItems::Template(_) = settings.items {
+            Self::validate_name(&format!("{} #{}", name, settings.max_supply))?;
+        }
+
+        //account to store the drop
+        let drop_account_info = next_account_info(account_info_iter)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //verify that the drop account matches the PDA for this drop
+        let (drop_pda, drop_pda_bump_seed) =
+            crate::instructions::derive_drop_account(program_id, authority.key, &name);
+        if &drop_pda != drop_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        // create drop_seeds (for invoke_signed)
+        let name_seed = crate::instructions::hash_nft_name(&name);
+        let drop_seeds_partial =
+            &crate::instructions::derive_drop_account_seeds(program_id, authority.key, &name_seed)
+                [..];
+
+        let mut drop_seeds = [&[] as &_; 7];
+        drop_seeds[..6].copy_from_slice(drop_seeds_partial);
+
+        let drop_pda_bump_seed = [drop_pda_bump_seed];
+        drop_seeds[6] = &drop_pda_bump_seed[..];
+
+        let drop = NftDrop {
+            account_type: AccountType::Drop,
+            version: DROP_VERSION,
+            authority: *authority.key,
+            created_slot: Clock::get()?.slot,
+            name,
+            minted: 0,
+            settings,
+        };
+
+        msg!("Calling the system program to create the drop account...");
+        Self::create_program_account(
+            program_id,
+            authority,
+            drop_account_info,
+            system_program,
+            &rent,
+            &drop_seeds,
+            &drop.try_to_vec()?,
+        )
+    }
+
+    //mints the next item of a drop to the buyer, once paid to the drop authority
+    fn process_mint_from_drop(
+        accounts: &[AccountInfo],
+        proof: Vec<[u8; 32]>,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //buyer paying, signing and receiving the item
+        let buyer = next_account_info(account_info_iter)?;
+        if !buyer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //drop the item is minted from
+        let drop_account_info = next_account_info(account_info_iter)?;
+        if drop_account_info.owner != program_id {
             return Err(ProgramError::IllegalOwner);
         }
+        let mut drop: NftDrop = Self::unpack_account(drop_account_info)?;
+        let (drop_pda, _) =
+            crate::instructions::derive_drop_account(program_id, &drop.authority, &drop.name);
+        if &drop_pda != drop_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
 
-        //get Rent sysvar
+        //authority of the drop, receiving the price
+        let authority = next_account_info(account_info_iter)?;
+        if &drop.authority != authority.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //mint, token, metadata and name record accounts of the item
+        let mint_account_info = next_account_info(account_info_iter)?;
+        let token_account_info = next_account_info(account_info_iter)?;
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let name_record_info = next_account_info(account_info_iter)?;
+
+        //retrieve Token Program account, SPL Token or Token-2022
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
         let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
 
+        //CHECK SCHEDULE
+        let clock = Clock::get()?;
+        let settings = &drop.settings;
+        if settings
+            .end_timestamp
+            .is_some_and(|timestamp| clock.unix_timestamp >= timestamp)
         {
-            let initialize_mint_ix = spl_token::instruction::initialize_mint(
-                token_program.key,
+            return Err(Error::DropEnded.into());
+        }
+
+        //mint counter of the buyer for the drop
+        let mint_counter_account_info = next_account_info(account_info_iter)?;
+        Self::count_wallet_mint(
+            program_id,
+            buyer,
+            drop_account_info.key,
+            mint_counter_account_info,
+            system_program,
+            &rent,
+            settings.wallet_limit,
+        )?;
+
+        //token accounts of the buyer and the drop, mint and token program, if paid in tokens
+        let token_payment_accounts = match settings.token_payment {
+            Some(_) => Some((
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+            )),
+            None => None,
+        };
+
+        //NFT presented by the buyer, if the drop is token gated
+        if let Some(token_gate) = &settings.token_gate {
+            let token_gate_accounts = TokenGateAccounts {
+                token_account: next_account_info(account_info_iter)?,
+                mint: next_account_info(account_info_iter)?,
+                token_program: next_account_info(account_info_iter)?,
+                metadata: match token_gate.requirement {
+                    GateRequirement::Collection(_) => Some(next_account_info(account_info_iter)?),
+                    GateRequirement::Mint(_) => None,
+                },
+                record: match token_gate.action {
+                    GateAction::MarkUsed => Some(next_account_info(account_info_iter)?),
+                    GateAction::Keep | GateAction::Burn => None,
+                },
+            };
+            Self::pass_token_gate(
+                program_id,
+                token_gate,
+                &token_gate_accounts,
+                buyer,
+                drop_account_info.key,
+                system_program,
+                &rent,
+            )?;
+        }
+
+        //before the go-live, only the wallets allowlisted for the current phase can mint
+        if settings.go_live_slot.is_some_and(|slot| clock.slot < slot)
+            || settings
+                .go_live_timestamp
+                .is_some_and(|timestamp| clock.unix_timestamp < timestamp)
+        {
+            let phase_index = settings
+                .phases
+                .iter()
+                .rposition(|phase| phase.start_timestamp <= clock.unix_timestamp)
+                .ok_or(Error::DropNotLive)?;
+            let phase = &settings.phases[phase_index];
+
+            if !crate::allowlist::verify_allowlist_proof(&phase.merkle_root, buyer.key, &proof) {
+                return Err(Error::NotAllowlisted.into());
+            }
+
+            //redemption account of the buyer for the phase
+            let redemption_account_info = next_account_info(account_info_iter)?;
+            Self::redeem_allowlist(
+                program_id,
+                buyer,
+                drop_account_info.key,
+                redemption_account_info,
+                system_program,
+                &rent,
+                phase_index as u8,
+                phase.limit,
+            )?;
+        }
+
+        //CHECK SUPPLY
+        let number = drop.minted + 1;
+        let (name, url) =
+            crate::instructions::drop_item(&drop, number).ok_or(Error::DropSoldOut)?;
+        Self::check_name_available(program_id, name_record_info, &name, mint_account_info.key)?;
+
+        //PAY PRICE
+        if settings.price > 0 {
+            let transfer_ix = solana_program::system_instruction::transfer(
+                buyer.key,
+                authority.key,
+                settings.price,
+            );
+
+            msg!("Calling the system program to pay the drop price...");
+            invoke(&transfer_ix, &[buyer.clone(), authority.clone()])?;
+        }
+
+        //PAY TOKENS
+        if let (Some(token_payment), Some((source, treasury, mint, payment_token_program))) =
+            (&settings.token_payment, token_payment_accounts)
+        {
+            //the token program checks that the source holds the same token
+            if &token_payment.treasury != treasury.key
+                || &token_payment.mint != mint.key
+                || &token_payment.token_program != payment_token_program.key
+                || treasury.owner != payment_token_program.key
+                || mint.owner != payment_token_program.key
+            {
+                return Err(Error::AccountMismatch.into());
+            }
+            let treasury_account = Self::unpack_token_account(treasury)?;
+            if treasury_account.mint != token_payment.mint {
+                return Err(Error::AccountMismatch.into());
+            }
+            let decimals =
+                StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint.data.borrow())?
+                    .base
+                    .decimals;
+
+            let transfer_ix = spl_token_2022::instruction::transfer_checked(
+                payment_token_program.key,
+                source.key,
                 mint.key,
-                //set the minting authority to the minter, temporary
-                // as we will see later we remove the authority
-                minter.key,
-                None,
-                0,
+                treasury.key,
+                buyer.key,
+                &[],
+                token_payment.amount,
+                decimals,
             )?;
 
-            msg!("Calling the token program to initialize the minting account...");
+            msg!("Calling the token program to pay the drop price...");
             invoke(
-                &initialize_mint_ix,
+                &transfer_ix,
                 &[
-                    //mint account
+                    source.clone(),
                     mint.clone(),
-                    rent_account.clone(),
-                    //token program
-                    token_program.clone(),
+                    treasury.clone(),
+                    buyer.clone(),
+                    payment_token_program.clone(),
                 ],
             )?;
         }
 
+        Self::mint_nft(
+            program_id,
+            &NftAccounts {
+                minter: buyer,
+                issuer: drop_account_info.key,
+                owner: buyer,
+                mint_account_info,
+                token_account_info,
+                token_program,
+                system_program,
+                rent_account,
+                associated_token_program: None,
+            },
+            &name,
+            None,
+            None,
+            false,
+            None,
+        )?;
+
+        Self::create_metadata_account(
+            program_id,
+            buyer,
+            metadata_account_info,
+            system_program,
+            &rent,
+            &Metadata {
+                account_type: AccountType::Metadata,
+                version: METADATA_VERSION,
+                mint: *mint_account_info.key,
+                minter: *drop_account_info.key,
+                created_slot: clock.slot,
+                display_name: name.clone(),
+                name,
+                url,
+                collection: None,
+                seller_fee_basis_points: 0,
+                creators: vec![],
+                edition: None,
+                update_authority: drop.authority,
+                is_mutable: true,
+                freeze_delegate: None,
+                frozen: false,
+                soulbound: false,
+            },
+        )?;
+
+        drop.minted = number;
+        Self::store_account(&drop, drop_account_info)
+    }
+
+    //creates the mint and token account PDAs of the NFT named `name`, mints it to `owner`
+    // and then hands the mint authority over to `mint_authority`, or revokes it if `None`.
+    // `freeze_authority` is set as the mint freeze authority.
+    // Token-2022 mints point to their metadata account, can be made non-transferable
+    // and can have a permanent delegate, allowed to burn the NFT
+    fn mint_nft(
+        program_id: &Pubkey,
+        accounts: &NftAccounts,
+        name: &str,
+        mint_authority: Option<&Pubkey>,
+        freeze_authority: Option<&Pubkey>,
+        non_transferable: bool,
+        permanent_delegate: Option<&Pubkey>,
+    ) -> ProgramResult {
+        let NftAccounts {
+            minter,
+            issuer,
+            mint_account_info,
+            token_account_info,
+            token_program,
+            system_program,
+            rent_account,
+            ..
+        } = *accounts;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //verify that the mint account matches the PDA for this NFT
+        let (mint_pda, mint_pda_bump_seed) =
+            crate::instructions::derive_mint_account(program_id, token_program.key, issuer, name);
+        if &mint_pda != mint_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //the name is hashed as it could be longer than the max seed length
+        let name_seed = crate::instructions::hash_nft_name(name);
+
+        // create mint_seeds (for invoke_signed)
+        let mint_seeds_partial = &crate::instructions::derive_mint_account_seeds(
+            program_id,
+            token_program.key,
+            issuer,
+            &name_seed,
+        )[..];
+
+        let mut mint_seeds = [&[] as &_; 8];
+        mint_seeds[..7].copy_from_slice(mint_seeds_partial);
+
+        let mint_pda_bump_seed = [mint_pda_bump_seed];
+        mint_seeds[7] = &mint_pda_bump_seed[..];
+
+        let mint_extensions = Self::mint_extensions(
+            token_program.key,
+            non_transferable,
+            permanent_delegate.is_some(),
+        )?;
+        let mint_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(
+            &mint_extensions,
+        )?;
+
+        //CREATE MINT ACCOUNT
+        msg!("Calling the system program to create the mint account...");
+        Self::create_pda_account(
+            minter,
+            mint_account_info,
+            system_program,
+            &rent,
+            mint_len,
+            token_program.key,
+            &mint_seeds,
+        )?;
+
+        //INITIALIZE MINT EXTENSIONS
+        for extension in mint_extensions {
+            let initialize_extension_ix = match extension {
+                ExtensionType::MetadataPointer => {
+                    spl_token_2022::extension::metadata_pointer::instruction::initialize(
+                        token_program.key,
+                        &mint_pda,
+                        None,
+                        Some(crate::instructions::derive_metadata_account(program_id, &mint_pda).0),
+                    )?
+                }
+                ExtensionType::NonTransferable => {
+                    spl_token_2022::instruction::initialize_non_transferable_mint(
+                        token_program.key,
+                        &mint_pda,
+                    )?
+                }
+                ExtensionType::PermanentDelegate => {
+                    spl_token_2022::instruction::initialize_permanent_delegate(
+                        token_program.key,
+                        &mint_pda,
+                        permanent_delegate.ok_or(Error::UnsupportedExtension)?,
+                    )?
+                }
+                _ => return Err(Error::UnsupportedExtension.into()),
+            };
+
+            msg!("Calling the token program to initialize a mint extension...");
+            invoke(
+                &initialize_extension_ix,
+                &[mint_account_info.clone(), token_program.clone()],
+            )?;
+        }
+
+        //INITIALIZE MINT ACCOUNT
         {
-            let initialize_token_account_ix = spl_token::instruction::initialize_account(
+            let initialize_mint_ix = spl_token_2022::instruction::initialize_mint(
                 token_program.key,
-                token_account.key,
-                mint.key,
-                receiver.key,
+                &mint_pda,
+                &mint_pda,
+                freeze_authority,
+                0,
             )?;
 
-            msg!("Calling the token program to initialize the token account...");
+            msg!("Calling the token program to initialize the minting account...");
             invoke(
-                &initialize_token_account_ix,
+                &initialize_mint_ix,
                 &[
-                    //account to initialize
-                    token_account.clone(),
                     //mint account
-                    mint.clone(),
-                    //the account owner
-                    receiver.clone(),
+                    mint_account_info.clone(),
                     rent_account.clone(),
                     //token program
                     token_program.clone(),
@@ -365,34 +1207,398 @@ impl Processor {
             )?;
         }
 
+        //CREATE TOKEN ACCOUNT
+        match accounts.associated_token_program {
+            Some(associated_token_program) => {
+                Self::create_associated_token_account(accounts, associated_token_program)?
+            }
+            None => Self::create_token_account_pda(program_id, accounts, name)?,
+        }
+
+        //MINT TO TOKEN ACCOUNT
         {
-            let mint_to_ix = spl_token::instruction::mint_to(
+            let mint_to_ix = spl_token_2022::instruction::mint_to(
                 token_program.key,
-                mint.key,
-                token_account.key,
-                minter.key,
-                &[minter.key],
+                &mint_pda,
+                token_account_info.key,
+                &mint_pda, //mint authority
+                &[&mint_pda],
                 1,
             )?;
 
             msg!("Calling the token program to mint the NFT to the token account...");
-            invoke(
+            invoke_signed(
                 &mint_to_ix,
                 &[
-                    mint.clone(),
-                    token_account.clone(),
+                    mint_account_info.clone(),
+                    token_account_info.clone(),
                     //minting authority
-                    minter.clone(),
+                    mint_account_info.clone(),
+                    token_program.clone(),
                 ],
+                &[&mint_seeds[..]],
             )?;
         }
 
+        //REVOKE OR HAND OVER MINT AUTHORITY
         {
-            let remove_mint_authority_ix = spl_token::instruction::set_authority(
+            let remove_mint_authority_ix = spl_token_2022::instruction::set_authority(
                 token_program.key,
-                mint.key,
-                None,
-                spl_token::instruction::AuthorityType::MintTokens,
+                &mint_pda,
+                mint_authority,
+                spl_token_2022::instruction::AuthorityType::MintTokens,
+                &mint_pda,
+                &[&mint_pda],
+            )?;
+
+            msg!("Calling the token program to revoke the mint authority...");
+            invoke_signed(
+                &remove_mint_authority_ix,
+                &[
+                    mint_account_info.clone(),
+                    //minting authority
+                    mint_account_info.clone(),
+                    token_program.clone(),
+                ],
+                &[&mint_seeds[..]],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //creates and initializes the token account PDA of the NFT named `name` for its owner
+    fn create_token_account_pda(
+        program_id: &Pubkey,
+        accounts: &NftAccounts,
+        name: &str,
+    ) -> ProgramResult {
+        let NftAccounts {
+            minter,
+            issuer,
+            owner,
+            mint_account_info,
+            token_account_info,
+            token_program,
+            system_program,
+            rent_account,
+            ..
+        } = *accounts;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //verify that the token account matches the PDA for this NFT
+        let (token_account_pda, token_account_pda_bump_seed) =
+            crate::instructions::derive_token_account(
+                program_id,
+                token_program.key,
+                issuer,
+                name,
+                owner.key,
+            );
+        if &token_account_pda != token_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //the name is hashed as it could be longer than the max seed length
+        let name_seed = crate::instructions::hash_nft_name(name);
+
+        let account_extensions = Self::token_account_extensions(token_program, mint_account_info)?;
+        let account_len = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+            &account_extensions,
+        )?;
+
+        // create token_account_seeds (for invoke_signed)
+        let token_account_seeds_partial = &crate::instructions::derive_token_account_seeds(
+            program_id,
+            token_program.key,
+            issuer,
+            &name_seed,
+            owner.key,
+        )[..];
+
+        let mut token_account_seeds = [&[] as &_; 9];
+        token_account_seeds[..8].copy_from_slice(token_account_seeds_partial);
+
+        let token_account_pda_bump_seed = [token_account_pda_bump_seed];
+        token_account_seeds[8] = &token_account_pda_bump_seed[..];
+
+        msg!("Calling the system program to create the token account...");
+        Self::create_pda_account(
+            minter,
+            token_account_info,
+            system_program,
+            &rent,
+            account_len,
+            token_program.key,
+            &token_account_seeds,
+        )?;
+
+        if account_extensions.contains(&ExtensionType::ImmutableOwner) {
+            let initialize_immutable_owner_ix =
+                spl_token_2022::instruction::initialize_immutable_owner(
+                    token_program.key,
+                    &token_account_pda,
+                )?;
+
+            msg!("Calling the token program to make the token account owner immutable...");
+            invoke(
+                &initialize_immutable_owner_ix,
+                &[token_account_info.clone(), token_program.clone()],
+            )?;
+        }
+
+        {
+            let initialize_token_account_ix = spl_token_2022::instruction::initialize_account(
+                token_program.key,
+                &token_account_pda,
+                mint_account_info.key,
+                owner.key,
+            )?;
+
+            msg!("Calling the token program to initialize the token account...");
+            invoke(
+                &initialize_token_account_ix,
+                &[
+                    //account to initialize
+                    token_account_info.clone(),
+                    //mint account
+                    mint_account_info.clone(),
+                    //the account owner
+                    owner.clone(),
+                    rent_account.clone(),
+                    //token program
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //creates the associated token account of the owner through the associated token account program
+    fn create_associated_token_account<'a>(
+        accounts: &NftAccounts<'a, '_>,
+        associated_token_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let NftAccounts {
+            minter,
+            owner,
+            mint_account_info,
+            token_account_info,
+            token_program,
+            system_program,
+            ..
+        } = *accounts;
+
+        //verify that the token account is the associated token account of the owner
+        let associated_token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                owner.key,
+                mint_account_info.key,
+                token_program.key,
+            );
+        if &associated_token_account != token_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        let create_associated_token_account_ix =
+            spl_associated_token_account::instruction::create_associated_token_account(
+                minter.key,
+                owner.key,
+                mint_account_info.key,
+                token_program.key,
+            );
+
+        msg!("Calling the associated token account program to create the token account...");
+        invoke(
+            &create_associated_token_account_ix,
+            &[
+                minter.clone(),
+                token_account_info.clone(),
+                owner.clone(),
+                mint_account_info.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                associated_token_program.clone(),
+            ],
+        )
+    }
+
+    //same as above, except the 2 accounts are already created
+    fn process_mint2(
+        accounts: &[AccountInfo],
+        name: String,
+        url: String,
+        options: MintOptions,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //account to mint the token and pay all the fees
+        let minter = next_account_info(account_info_iter)?;
+        if !minter.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        Self::validate_name(&name)?;
+        Self::validate_url(&url, ALLOWED_URL_SCHEMES)?;
+        Self::validate_creators(&options.creators, options.seller_fee_basis_points)?;
+        Self::validate_max_editions(options.max_editions)?;
+        //prints are named after the master edition, the last one has the longest name
+        if let Some(max_supply) = options.max_editions {
+            Self::validate_name(&crate::instructions::print_edition_name(&name, max_supply))?;
+        }
+
+        //the accounts are created by the client without room for extensions,
+        // which soulbound NFTs need
+        if options.non_transferable || options.soulbound {
+            return Err(Error::UnsupportedExtension.into());
+        }
+
+        //account to receive the token
+        let receiver = next_account_info(account_info_iter)?;
+
+        //account to mint the token
+        let mint = next_account_info(account_info_iter)?;
+        //account to hold the token
+        let token_account = next_account_info(account_info_iter)?;
+
+        //token program, SPL Token or Token-2022
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+        //check that `mint` and `token_account` are accounts of the token program
+        if mint.owner != token_program.key || token_account.owner != token_program.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        //get Rent sysvar
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //account to store the NFT metadata
+        let metadata_account = next_account_info(account_info_iter)?;
+
+        //System program, to create the metadata account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //accounts to store the master edition and its edition marker, if any
+        let master_edition_accounts = match options.max_editions {
+            Some(_) => Some((
+                next_account_info(account_info_iter)?,
+                next_account_info(account_info_iter)?,
+            )),
+            None => None,
+        };
+
+        //name record, to register the name across all minters or check it isn't
+        let name_record_info = next_account_info(account_info_iter)?;
+
+        //verified creators sign either as one of the accounts above or as co-signers
+        Self::check_creator_signatures(&options.creators, accounts)?;
+
+        Self::check_name_available(program_id, name_record_info, &name, mint.key)?;
+        if options.register_name {
+            Self::create_name_record(
+                program_id,
+                minter,
+                name_record_info,
+                system_program,
+                &rent,
+                &name,
+                mint.key,
+            )?;
+        }
+
+        //master editions keep their mint authority through the master edition PDA
+        let master_edition_pda = options
+            .max_editions
+            .map(|_| crate::instructions::derive_master_edition_account(program_id, mint.key).0);
+
+        //freezable NFTs have the freeze authority PDA as mint freeze authority
+        let freeze_authority_pda = options
+            .freeze_delegate
+            .map(|_| crate::instructions::derive_freeze_authority(program_id, mint.key).0);
+
+        {
+            let initialize_mint_ix = spl_token_2022::instruction::initialize_mint(
+                token_program.key,
+                mint.key,
+                //set the minting authority to the minter, temporary
+                // as we will see later we remove the authority
+                minter.key,
+                freeze_authority_pda.as_ref(),
+                0,
+            )?;
+
+            msg!("Calling the token program to initialize the minting account...");
+            invoke(
+                &initialize_mint_ix,
+                &[
+                    //mint account
+                    mint.clone(),
+                    rent_account.clone(),
+                    //token program
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        {
+            let initialize_token_account_ix = spl_token_2022::instruction::initialize_account(
+                token_program.key,
+                token_account.key,
+                mint.key,
+                receiver.key,
+            )?;
+
+            msg!("Calling the token program to initialize the token account...");
+            invoke(
+                &initialize_token_account_ix,
+                &[
+                    //account to initialize
+                    token_account.clone(),
+                    //mint account
+                    mint.clone(),
+                    //the account owner
+                    receiver.clone(),
+                    rent_account.clone(),
+                    //token program
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        {
+            let mint_to_ix = spl_token_2022::instruction::mint_to(
+                token_program.key,
+                mint.key,
+                token_account.key,
+                minter.key,
+                &[minter.key],
+                1,
+            )?;
+
+            msg!("Calling the token program to mint the NFT to the token account...");
+            invoke(
+                &mint_to_ix,
+                &[
+                    mint.clone(),
+                    token_account.clone(),
+                    //minting authority
+                    minter.clone(),
+                ],
+            )?;
+        }
+
+        {
+            let remove_mint_authority_ix = spl_token_2022::instruction::set_authority(
+                token_program.key,
+                mint.key,
+                master_edition_pda.as_ref(),
+                spl_token_2022::instruction::AuthorityType::MintTokens,
                 minter.key,
                 &[minter.key],
             )?;
@@ -408,6 +1614,1910 @@ impl Processor {
             )?;
         }
 
+        Self::create_metadata_account(
+            program_id,
+            minter,
+            metadata_account,
+            system_program,
+            &rent,
+            &Metadata {
+                account_type: AccountType::Metadata,
+                version: METADATA_VERSION,
+                mint: *mint.key,
+                minter: *minter.key,
+                created_slot: Clock::get()?.slot,
+                display_name: name.clone(),
+                name,
+                url,
+                collection: options.collection.map(|key| CollectionItem {
+                    key,
+                    verified: false,
+                }),
+                seller_fee_basis_points: options.seller_fee_basis_points,
+                creators: options.creators,
+                edition: None,
+                update_authority: options.update_authority.unwrap_or(*minter.key),
+                is_mutable: !options.immutable,
+                freeze_delegate: options.freeze_delegate,
+                frozen: false,
+                soulbound: options.soulbound,
+            },
+        )?;
+
+        if let (Some(max_supply), Some((master_edition_account, edition_marker_account))) =
+            (options.max_editions, master_edition_accounts)
+        {
+            Self::create_master_edition(
+                program_id,
+                minter,
+                mint.key,
+                master_edition_account,
+                edition_marker_account,
+                system_program,
+                &rent,
+                max_supply,
+            )?;
+        }
+
         Ok(())
     }
+
+    //moves the NFT from the token account PDA of the current owner
+    // to the one of the new owner, creating it if needed and closing the old one
+    fn process_transfer(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //current owner of the NFT, pays for the new token account
+        let owner = next_account_info(account_info_iter)?;
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //account to receive the NFT
+        let new_owner = next_account_info(account_info_iter)?;
+        if new_owner.key == owner.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT, needed to derive the token accounts
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+
+        //account currently holding the NFT
+        let source_account_info = next_account_info(account_info_iter)?;
+
+        //account that will hold the NFT
+        let destination_account_info = next_account_info(account_info_iter)?;
+
+        //retrieve Token Program account, SPL Token or Token-2022
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //verify that the source account matches the PDA of the current owner
+        let (source_pda, _) = crate::instructions::derive_token_account(
+            program_id,
+            token_program.key,
+            &metadata.minter,
+            metadata.name.as_str(),
+            owner.key,
+        );
+        if &source_pda != source_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //verify that the destination account matches the PDA of the new owner
+        let (destination_pda, destination_pda_bump_seed) =
+            crate::instructions::derive_token_account(
+                program_id,
+                token_program.key,
+                &metadata.minter,
+                metadata.name.as_str(),
+                new_owner.key,
+            );
+        if &destination_pda != destination_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //the destination account may already exist if it was created by a previous transfer
+        if destination_account_info.owner != token_program.key {
+            // create destination_seeds (for invoke_signed)
+            let name_seed = crate::instructions::hash_nft_name(metadata.name.as_str());
+            let destination_seeds_partial = &crate::instructions::derive_token_account_seeds(
+                program_id,
+                token_program.key,
+                &metadata.minter,
+                &name_seed,
+                new_owner.key,
+            )[..];
+
+            let mut destination_seeds = [&[] as &_; 9];
+            destination_seeds[..8].copy_from_slice(destination_seeds_partial);
+
+            let destination_pda_bump_seed = [destination_pda_bump_seed];
+            destination_seeds[8] = &destination_pda_bump_seed[..];
+
+            let account_extensions =
+                Self::token_account_extensions(token_program, mint_account_info)?;
+            let account_len = ExtensionType::try_calculate_account_len::<
+                spl_token_2022::state::Account,
+            >(&account_extensions)?;
+
+            //CREATE DESTINATION TOKEN ACCOUNT
+            // which anyone could have funded beforehand, to block transfers to the new owner
+            msg!("Calling the system program to create the destination token account...");
+            Self::create_pda_account(
+                owner,
+                destination_account_info,
+                system_program,
+                &rent,
+                account_len,
+                token_program.key,
+                &destination_seeds,
+            )?;
+
+            if account_extensions.contains(&ExtensionType::ImmutableOwner) {
+                let initialize_immutable_owner_ix =
+                    spl_token_2022::instruction::initialize_immutable_owner(
+                        token_program.key,
+                        &destination_pda,
+                    )?;
+
+                msg!("Calling the token program to make the destination owner immutable...");
+                invoke(
+                    &initialize_immutable_owner_ix,
+                    &[destination_account_info.clone(), token_program.clone()],
+                )?;
+            }
+
+            //INITIALIZE DESTINATION TOKEN ACCOUNT
+            {
+                let initialize_token_account_ix = spl_token_2022::instruction::initialize_account(
+                    token_program.key,
+                    &destination_pda,
+                    mint_account_info.key,
+                    new_owner.key,
+                )?;
+
+                msg!("Calling the token program to initialize the destination token account...");
+                invoke(
+                    &initialize_token_account_ix,
+                    &[
+                        destination_account_info.clone(),
+                        mint_account_info.clone(),
+                        new_owner.clone(),
+                        rent_account.clone(),
+                        token_program.clone(),
+                    ],
+                )?;
+            }
+        }
+
+        //TRANSFER THE NFT
+        {
+            let transfer_ix = spl_token_2022::instruction::transfer_checked(
+                token_program.key,
+                &source_pda,
+                mint_account_info.key,
+                &destination_pda,
+                owner.key,
+                &[],
+                1,
+                0,
+            )?;
+
+            msg!("Calling the token program to transfer the NFT...");
+            invoke(
+                &transfer_ix,
+                &[
+                    source_account_info.clone(),
+                    mint_account_info.clone(),
+                    destination_account_info.clone(),
+                    owner.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        //CLOSE SOURCE TOKEN ACCOUNT
+        {
+            let close_account_ix = spl_token_2022::instruction::close_account(
+                token_program.key,
+                &source_pda,
+                owner.key,
+                owner.key,
+                &[],
+            )?;
+
+            msg!("Calling the token program to close the source token account...");
+            invoke(
+                &close_account_ix,
+                &[
+                    source_account_info.clone(),
+                    //lamports destination
+                    owner.clone(),
+                    //account owner
+                    owner.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //burns the NFT, closing its token account and metadata
+    // and refunding their lamports to the given recipient
+    fn process_burn(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //current owner of the NFT
+        let owner = next_account_info(account_info_iter)?;
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT, closed at the end
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+        //only the minter can get rid of a soulbound NFT, see `process_revoke`
+        if metadata.soulbound {
+            return Err(Error::SoulboundNft.into());
+        }
+
+        //account holding the NFT
+        let token_account_info = next_account_info(account_info_iter)?;
+
+        //account receiving the lamports of the closed accounts
+        let recipient = next_account_info(account_info_iter)?;
+
+        //retrieve SPL Token or Token-2022 Program account
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+        if mint_account_info.owner != token_program.key
+            || token_account_info.owner != token_program.key
+        {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        //master edition and edition marker PDAs of the NFT, only created for master editions
+        let master_edition_account = next_account_info(account_info_iter)?;
+        let edition_marker_account = next_account_info(account_info_iter)?;
+        let (master_edition_pda, _) =
+            crate::instructions::derive_master_edition_account(program_id, mint_account_info.key);
+        let (edition_marker_pda, _) =
+            crate::instructions::derive_edition_marker_account(program_id, mint_account_info.key);
+        if &master_edition_pda != master_edition_account.key
+            || &edition_marker_pda != edition_marker_account.key
+        {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //BURN THE NFT
+        // the token program makes sure `owner` holds the NFT
+        {
+            let burn_ix = spl_token_2022::instruction::burn(
+                token_program.key,
+                token_account_info.key,
+                mint_account_info.key,
+                owner.key,
+                &[],
+                1,
+            )?;
+
+            msg!("Calling the token program to burn the NFT...");
+            invoke(
+                &burn_ix,
+                &[
+                    token_account_info.clone(),
+                    mint_account_info.clone(),
+                    owner.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        //CLOSE TOKEN ACCOUNT
+        {
+            let close_account_ix = spl_token_2022::instruction::close_account(
+                token_program.key,
+                token_account_info.key,
+                recipient.key,
+                owner.key,
+                &[],
+            )?;
+
+            msg!("Calling the token program to close the token account...");
+            invoke(
+                &close_account_ix,
+                &[
+                    token_account_info.clone(),
+                    //lamports destination
+                    recipient.clone(),
+                    //account owner
+                    owner.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        //CLOSE METADATA ACCOUNT
+        Self::close_program_account(metadata_account_info, recipient)?;
+
+        //CLOSE MASTER EDITION ACCOUNTS
+        if master_edition_account.owner == program_id {
+            Self::close_program_account(master_edition_account, recipient)?;
+            Self::close_program_account(edition_marker_account, recipient)?;
+        }
+
+        Ok(())
+    }
+
+    //closes a token account left uninitialized or empty by `Mint2`, returning its lamports to the payer
+    fn process_reclaim(accounts: &[AccountInfo]) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //account receiving the lamports, owner of the token account once initialized
+        let payer = next_account_info(account_info_iter)?;
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //token account to close
+        let token_account_info = next_account_info(account_info_iter)?;
+
+        //mint of the token account
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //retrieve Token Program account, SPL Token or Token-2022
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+        if token_account_info.owner != token_program.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        //get Rent sysvar, needed to initialize the token account
+        let rent_account = next_account_info(account_info_iter)?;
+
+        if !crate::instructions::is_reclaimable(
+            token_account_info.owner,
+            &token_account_info.data.borrow(),
+            payer.key,
+        ) {
+            return Err(Error::NotReclaimable.into());
+        }
+
+        let token_account = spl_token_2022::state::Account::unpack_unchecked(
+            token_account_info
+                .data
+                .borrow()
+                .get(..spl_token_2022::state::Account::LEN)
+                .ok_or(ProgramError::InvalidAccountData)?,
+        )?;
+
+        //INITIALIZE TOKEN ACCOUNT
+        // the token program only closes initialized accounts
+        if token_account.state == spl_token_2022::state::AccountState::Uninitialized {
+            //an uninitialized account doesn't record who funded it, only the holder
+            // of its keypair can claim it
+            if !token_account_info.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+
+            let initialize_token_account_ix = spl_token_2022::instruction::initialize_account(
+                token_program.key,
+                token_account_info.key,
+                mint_account_info.key,
+                payer.key,
+            )?;
+
+            msg!("Calling the token program to initialize the token account...");
+            invoke(
+                &initialize_token_account_ix,
+                &[
+                    token_account_info.clone(),
+                    mint_account_info.clone(),
+                    payer.clone(),
+                    rent_account.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        //CLOSE TOKEN ACCOUNT
+        {
+            let close_account_ix = spl_token_2022::instruction::close_account(
+                token_program.key,
+                token_account_info.key,
+                payer.key,
+                payer.key,
+                &[],
+            )?;
+
+            msg!("Calling the token program to close the token account...");
+            invoke(
+                &close_account_ix,
+                &[
+                    token_account_info.clone(),
+                    //lamports destination and account owner
+                    payer.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    //closes the record of a name once its NFT is burned, anyone can release it
+    fn process_release_name(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //registrant of the name, receives the lamports of the record
+        let registrant = next_account_info(account_info_iter)?;
+
+        //mint of the NFT holding the name
+        let mint_account_info = next_account_info(account_info_iter)?;
+        Self::check_token_program_owner(mint_account_info)?;
+
+        //record of the name
+        let name_record_info = next_account_info(account_info_iter)?;
+        if name_record_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let name_record: NameRecord = Self::unpack_account(name_record_info)?;
+        let (name_record_pda, _) =
+            crate::instructions::derive_name_record_account(program_id, &name_record.name);
+        if &name_record_pda != name_record_info.key
+            || &name_record.mint != mint_account_info.key
+            || &name_record.registrant != registrant.key
+        {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //the NFT must be burned
+        let mint_data = mint_account_info.data.borrow();
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        if mint.base.supply != 0 {
+            return Err(Error::NameStillInUse.into());
+        }
+
+        //CLOSE NAME RECORD
+        Self::close_program_account(name_record_info, registrant)
+    }
+
+    //creates a collection PDA owned by the signing authority
+    fn process_create_collection(
+        accounts: &[AccountInfo],
+        name: String,
+        url: String,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //authority of the collection, pays for the collection account
+        let authority = next_account_info(account_info_iter)?;
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        Self::validate_name(&name)?;
+        Self::validate_url(&url, ALLOWED_URL_SCHEMES)?;
+
+        //account to store the collection
+        let collection_account_info = next_account_info(account_info_iter)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //verify that the collection account matches the PDA for this collection
+        let (collection_pda, collection_pda_bump_seed) =
+            crate::instructions::derive_collection_account(program_id, authority.key, &name);
+        if &collection_pda != collection_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        // create collection_seeds (for invoke_signed)
+        let name_seed = crate::instructions::hash_nft_name(&name);
+        let collection_seeds_partial = &crate::instructions::derive_collection_account_seeds(
+            program_id,
+            authority.key,
+            &name_seed,
+        )[..];
+
+        let mut collection_seeds = [&[] as &_; 7];
+        collection_seeds[..6].copy_from_slice(collection_seeds_partial);
+
+        let collection_pda_bump_seed = [collection_pda_bump_seed];
+        collection_seeds[6] = &collection_pda_bump_seed[..];
+
+        let collection = Collection {
+            account_type: AccountType::Collection,
+            version: COLLECTION_VERSION,
+            authority: *authority.key,
+            created_slot: Clock::get()?.slot,
+            name,
+            url,
+        };
+
+        msg!("Calling the system program to create the collection account...");
+        Self::create_program_account(
+            program_id,
+            authority,
+            collection_account_info,
+            system_program,
+            &rent,
+            &collection_seeds,
+            &collection.try_to_vec()?,
+        )
+    }
+
+    //marks the NFT as verified member of its collection, signed by the collection authority
+    fn process_verify_collection_item(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //authority of the collection
+        let authority = next_account_info(account_info_iter)?;
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //collection the NFT claims to belong to
+        let collection_account_info = next_account_info(account_info_iter)?;
+        if collection_account_info.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let collection: Collection = Self::unpack_account(collection_account_info)?;
+        if &collection.authority != authority.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        //the collection must be the PDA created by `CreateCollection`
+        let (collection_pda, _) = crate::instructions::derive_collection_account(
+            program_id,
+            &collection.authority,
+            &collection.name,
+        );
+        if &collection_pda != collection_account_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let mut metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+
+        match metadata.collection.as_mut() {
+            Some(item) if &item.key == collection_account_info.key => item.verified = true,
+            _ => return Err(Error::CollectionMismatch.into()),
+        }
+
+        Self::store_account(&metadata, metadata_account_info)
+    }
+
+    //marks the signing creator as verified in the NFT metadata
+    fn process_sign_creator(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //creator of the NFT
+        let creator = next_account_info(account_info_iter)?;
+        if !creator.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let mut metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+
+        metadata
+            .creators
+            .iter_mut()
+            .find(|c| &c.address == creator.key)
+            .ok_or(Error::CreatorNotFound)?
+            .verified = true;
+
+        Self::store_account(&metadata, metadata_account_info)
+    }
+
+    //makes sure every creator marked as verified signed the transaction, matching
+    // them by key among all the accounts of the instruction, whatever their position
+    fn check_creator_signatures(creators: &[Creator], accounts: &[AccountInfo]) -> ProgramResult {
+        for creator in creators.iter().filter(|c| c.verified) {
+            let signed = accounts
+                .iter()
+                .any(|account| account.key == &creator.address && account.is_signer);
+            if !signed {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+        }
+
+        Ok(())
+    }
+
+    //mints a numbered copy of a master edition as its own NFT,
+    // signed by the owner of the master edition
+    fn process_print_edition(
+        accounts: &[AccountInfo],
+        edition: u64,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //owner of the master edition, pays for the print accounts
+        let master_owner = next_account_info(account_info_iter)?;
+        if !master_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //final recipient of the print
+        let owner = next_account_info(account_info_iter)?;
+
+        //mint of the master edition
+        let master_mint = next_account_info(account_info_iter)?;
+
+        //metadata of the master edition
+        let master_metadata_account = next_account_info(account_info_iter)?;
+        let master_metadata =
+            Self::load_metadata(program_id, master_mint.key, master_metadata_account)?;
+
+        //master edition account, keeping track of the supply
+        let master_edition_account = next_account_info(account_info_iter)?;
+        let mut master_edition: MasterEdition = Self::load_account(
+            program_id,
+            &crate::instructions::derive_master_edition_account(program_id, master_mint.key).0,
+            master_edition_account,
+        )?;
+
+        //edition marker, keeping track of the printed edition numbers
+        let edition_marker_account = next_account_info(account_info_iter)?;
+        let mut edition_marker: EditionMarker = Self::load_account(
+            program_id,
+            &crate::instructions::derive_edition_marker_account(program_id, master_mint.key).0,
+            edition_marker_account,
+        )?;
+
+        //token account holding the master edition
+        let master_token_account = next_account_info(account_info_iter)?;
+
+        //accounts of the print
+        let mint_account_info = next_account_info(account_info_iter)?;
+        let token_account_info = next_account_info(account_info_iter)?;
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let name_record_info = next_account_info(account_info_iter)?;
+
+        //retrieve Token Program account, SPL Token or Token-2022
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        //only the owner of the master edition can print it
+        Self::check_nft_owner(
+            token_program,
+            master_token_account,
+            master_mint.key,
+            master_owner.key,
+        )?;
+
+        //mark the edition number as printed
+        if edition == 0 || edition > master_edition.max_supply {
+            return Err(Error::InvalidEditionNumber.into());
+        }
+        let (byte, bit) = (((edition - 1) / 8) as usize, (edition - 1) % 8);
+        if edition_marker.bitmap[byte] & (1 << bit) != 0 {
+            return Err(Error::EditionAlreadyPrinted.into());
+        }
+        edition_marker.bitmap[byte] |= 1 << bit;
+        master_edition.supply += 1;
+
+        Self::store_account(&edition_marker, edition_marker_account)?;
+        Self::store_account(&master_edition, master_edition_account)?;
+
+        let name = crate::instructions::print_edition_name(&master_metadata.name, edition);
+        Self::check_name_available(program_id, name_record_info, &name, mint_account_info.key)?;
+
+        Self::mint_nft(
+            program_id,
+            &NftAccounts {
+                minter: master_owner,
+                issuer: master_owner.key,
+                owner,
+                mint_account_info,
+                token_account_info,
+                token_program,
+                system_program,
+                rent_account,
+                associated_token_program: None,
+            },
+            &name,
+            None,
+            None,
+            false,
+            None,
+        )?;
+
+        Self::create_metadata_account(
+            program_id,
+            master_owner,
+            metadata_account_info,
+            system_program,
+            &rent,
+            &Metadata {
+                account_type: AccountType::Metadata,
+                version: METADATA_VERSION,
+                mint: *mint_account_info.key,
+                minter: *master_owner.key,
+                created_slot: Clock::get()?.slot,
+                display_name: name.clone(),
+                name,
+                url: master_metadata.url,
+                collection: master_metadata.collection,
+                seller_fee_basis_points: master_metadata.seller_fee_basis_points,
+                creators: master_metadata.creators,
+                edition: Some(Edition {
+                    master_mint: *master_mint.key,
+                    number: edition,
+                }),
+                update_authority: master_metadata.update_authority,
+                is_mutable: master_metadata.is_mutable,
+                freeze_delegate: None,
+                frozen: false,
+                soulbound: false,
+            },
+        )
+    }
+
+    //changes the mutable fields of the metadata, signed by its update authority
+    fn process_update_metadata(
+        accounts: &[AccountInfo],
+        display_name: Option<String>,
+        url: Option<String>,
+        update_authority: Option<Pubkey>,
+        is_mutable: Option<bool>,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //update authority of the NFT, pays for any metadata growth
+        let authority = next_account_info(account_info_iter)?;
+        if !authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let mut metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+        if &metadata.update_authority != authority.key {
+            return Err(Error::InvalidUpdateAuthority.into());
+        }
+        if !metadata.is_mutable {
+            return Err(Error::MetadataImmutable.into());
+        }
+
+        //retrieve System Program account
+        let system_program = next_account_info(account_info_iter)?;
+        if !solana_program::system_program::check_id(system_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //get Rent sysvar to calculate rent stuff
+        let rent_account = next_account_info(account_info_iter)?;
+        let rent = Rent::from_account_info(rent_account)?;
+
+        if let Some(display_name) = display_name {
+            Self::validate_name(&display_name)?;
+            metadata.display_name = display_name;
+        }
+        if let Some(url) = url {
+            Self::validate_url(&url, ALLOWED_URL_SCHEMES)?;
+            metadata.url = url;
+        }
+        if let Some(update_authority) = update_authority {
+            metadata.update_authority = update_authority;
+        }
+        //once cleared the flag can't be set again, as the metadata can't be updated anymore
+        if let Some(is_mutable) = is_mutable {
+            metadata.is_mutable = is_mutable;
+        }
+
+        Self::store_resized_account(
+            &metadata,
+            metadata_account_info,
+            authority,
+            system_program,
+            &rent,
+        )
+    }
+
+    //freezes or thaws the token account holding the NFT, signed by its freeze delegate
+    fn process_freeze(
+        accounts: &[AccountInfo],
+        freeze: bool,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //freeze delegate of the NFT
+        let delegate = next_account_info(account_info_iter)?;
+        if !delegate.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let mut metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+        if metadata.freeze_delegate.as_ref() != Some(delegate.key) {
+            return Err(Error::InvalidFreezeDelegate.into());
+        }
+        if metadata.soulbound {
+            return Err(Error::SoulboundNft.into());
+        }
+
+        //account holding the NFT
+        let token_account_info = next_account_info(account_info_iter)?;
+
+        //freeze authority of the mint
+        let freeze_authority_info = next_account_info(account_info_iter)?;
+
+        //retrieve SPL Token or Token-2022 Program account
+        let token_program = next_account_info(account_info_iter)?;
+        Self::check_token_program(token_program)?;
+
+        Self::set_frozen(
+            program_id,
+            token_program,
+            token_account_info,
+            mint_account_info,
+            freeze_authority_info,
+            freeze,
+        )?;
+
+        metadata.frozen = freeze;
+        Self::store_account(&metadata, metadata_account_info)
+    }
+
+    //burns a soulbound NFT through its permanent delegate, signed by its minter.
+    // The empty token account belongs to the holder and is left open
+    fn process_revoke(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+
+        //minter of the NFT, receives the lamports of the metadata account
+        let issuer = next_account_info(account_info_iter)?;
+        if !issuer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        //mint of the NFT
+        let mint_account_info = next_account_info(account_info_iter)?;
+
+        //metadata of the NFT, closed at the end
+        let metadata_account_info = next_account_info(account_info_iter)?;
+        let metadata =
+            Self::load_metadata(program_id, mint_account_info.key, metadata_account_info)?;
+        if &metadata.minter != issuer.key {
+            return Err(Error::InvalidIssuer.into());
+        }
+        if !metadata.soulbound {
+            return Err(Error::NotSoulbound.into());
+        }
+
+        //account holding the NFT
+        let token_account_info = next_account_info(account_info_iter)?;
+
+        //freeze authority PDA of the mint, permanent delegate of soulbound NFTs
+        let freeze_authority_info = next_account_info(account_info_iter)?;
+        let (freeze_authority_pda, _) =
+            crate::instructions::derive_freeze_authority(program_id, mint_account_info.key);
+        if &freeze_authority_pda != freeze_authority_info.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //retrieve Token-2022 Program account, the only one with permanent delegates
+        let token_program = next_account_info(account_info_iter)?;
+        if !spl_token_2022::check_id(token_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        //BURN THE NFT
+        {
+            let burn_ix = spl_token_2022::instruction::burn(
+                token_program.key,
+                token_account_info.key,
+                mint_account_info.key,
+                freeze_authority_info.key,
+                &[],
+                1,
+            )?;
+
+            msg!("Calling the token program to burn the NFT...");
+            Self::invoke_signed_by_freeze_authority(
+                program_id,
+                mint_account_info.key,
+                &burn_ix,
+                &[
+                    token_account_info.clone(),
+                    mint_account_info.clone(),
+                    freeze_authority_info.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+
+        //CLOSE METADATA ACCOUNT
+        Self::close_program_account(metadata_account_info, issuer)?;
+
+        Ok(())
+    }
+
+    //freezes or thaws `token_account` through the freeze authority PDA of `mint`
+    fn set_frozen<'a>(
+        program_id: &Pubkey,
+        token_program: &AccountInfo<'a>,
+        token_account: &AccountInfo<'a>,
+        mint: &AccountInfo<'a>,
+        freeze_authority: &AccountInfo<'a>,
+        freeze: bool,
+    ) -> ProgramResult {
+        //verify that the freeze authority matches the PDA for this NFT
+        let (freeze_authority_pda, _) =
+            crate::instructions::derive_freeze_authority(program_id, mint.key);
+        if &freeze_authority_pda != freeze_authority.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        let freeze_ix = if freeze {
+            msg!("Calling the token program to freeze the token account...");
+            spl_token_2022::instruction::freeze_account(
+                token_program.key,
+                token_account.key,
+                mint.key,
+                freeze_authority.key,
+                &[],
+            )?
+        } else {
+            msg!("Calling the token program to thaw the token account...");
+            spl_token_2022::instruction::thaw_account(
+                token_program.key,
+                token_account.key,
+                mint.key,
+                freeze_authority.key,
+                &[],
+            )?
+        };
+
+        Self::invoke_signed_by_freeze_authority(
+            program_id,
+            mint.key,
+            &freeze_ix,
+            &[
+                token_account.clone(),
+                mint.clone(),
+                freeze_authority.clone(),
+                token_program.clone(),
+            ],
+        )
+    }
+
+    //invokes `instruction` signed by the freeze authority PDA of `mint`
+    fn invoke_signed_by_freeze_authority(
+        program_id: &Pubkey,
+        mint: &Pubkey,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+    ) -> ProgramResult {
+        let (_, freeze_authority_pda_bump_seed) =
+            crate::instructions::derive_freeze_authority(program_id, mint);
+
+        // create freeze_authority_seeds (for invoke_signed)
+        let freeze_authority_seeds_partial =
+            &crate::instructions::derive_freeze_authority_seeds(program_id, mint)[..];
+
+        let mut freeze_authority_seeds = [&[] as &_; 5];
+        freeze_authority_seeds[..4].copy_from_slice(freeze_authority_seeds_partial);
+
+        let freeze_authority_pda_bump_seed = [freeze_authority_pda_bump_seed];
+        freeze_authority_seeds[4] = &freeze_authority_pda_bump_seed[..];
+
+        invoke_signed(instruction, account_infos, &[&freeze_authority_seeds])
+    }
+
+    //makes sure `token_program` is either SPL Token or Token-2022
+    fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+        if !spl_token::check_id(token_program.key) && !spl_token_2022::check_id(token_program.key) {
+            return Err(Error::AccountMismatch.into());
+        }
+        Ok(())
+    }
+
+    //makes sure `account` is owned by SPL Token or Token-2022
+    fn check_token_program_owner(account: &AccountInfo) -> ProgramResult {
+        if !spl_token::check_id(account.owner) && !spl_token_2022::check_id(account.owner) {
+            return Err(ProgramError::IllegalOwner);
+        }
+        Ok(())
+    }
+
+    //extensions of a token account of `mint`, Token-2022 accounts have immutable owners
+    // plus the extensions required by the mint
+    fn token_account_extensions(
+        token_program: &AccountInfo,
+        mint: &AccountInfo,
+    ) -> Result<Vec<ExtensionType>, ProgramError> {
+        if !spl_token_2022::check_id(token_program.key) {
+            return Ok(vec![]);
+        }
+
+        let mint_data = mint.data.borrow();
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+        let mut account_extensions =
+            ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+        account_extensions.push(ExtensionType::ImmutableOwner);
+        Ok(account_extensions)
+    }
+
+    //reads an initialized token account of SPL Token or Token-2022, ignoring its extensions
+    fn unpack_token_account(
+        token_account: &AccountInfo,
+    ) -> Result<spl_token_2022::state::Account, ProgramError> {
+        let data = token_account.data.borrow();
+        Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?.base)
+    }
+
+    //extensions of the mint of an NFT, only available with Token-2022
+    fn mint_extensions(
+        token_program: &Pubkey,
+        non_transferable: bool,
+        permanent_delegate: bool,
+    ) -> Result<Vec<ExtensionType>, ProgramError> {
+        if !spl_token_2022::check_id(token_program) {
+            if non_transferable || permanent_delegate {
+                return Err(Error::UnsupportedExtension.into());
+            }
+            return Ok(vec![]);
+        }
+
+        let mut extensions = vec![ExtensionType::MetadataPointer];
+        if non_transferable {
+            extensions.push(ExtensionType::NonTransferable);
+        }
+        if permanent_delegate {
+            extensions.push(ExtensionType::PermanentDelegate);
+        }
+        Ok(extensions)
+    }
+
+    //makes sure `token_account` holds the NFT minted with `mint` and is owned by `owner`
+    fn check_nft_owner(
+        token_program: &AccountInfo,
+        token_account: &AccountInfo,
+        mint: &Pubkey,
+        owner: &Pubkey,
+    ) -> ProgramResult {
+        if token_account.owner != token_program.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        let token_account = Self::unpack_token_account(token_account)?;
+        if &token_account.mint != mint || &token_account.owner != owner || token_account.amount != 1
+        {
+            return Err(Error::NotNftOwner.into());
+        }
+
+        Ok(())
+    }
+
+    /// Check that the maximum number of prints of a master edition,
+    /// if any, is between 1 and `MAX_EDITION_SUPPLY`
+    pub fn validate_max_editions(max_editions: Option<u64>) -> Result<(), Error> {
+        match max_editions {
+            Some(max_supply) if max_supply == 0 || max_supply > MAX_EDITION_SUPPLY => {
+                Err(Error::InvalidMaxSupply)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that `name` is not longer than `MAX_NAME_LENGTH`
+    pub fn validate_name(name: &str) -> Result<(), Error> {
+        if name.len() > MAX_NAME_LENGTH {
+            return Err(Error::NameTooLong);
+        }
+        Ok(())
+    }
+
+    /// Check that `url` is a well formed URL, not longer than `MAX_URL_LENGTH`
+    /// and using one of `allowed_schemes`
+    pub fn validate_url(url: &str, allowed_schemes: &[&str]) -> Result<(), Error> {
+        if url.len() > MAX_URL_LENGTH {
+            return Err(Error::UrlTooLong);
+        }
+
+        let parsed = url::Url::parse(url).map_err(|_| Error::InvalidUrl)?;
+        if !allowed_schemes.contains(&parsed.scheme()) {
+            return Err(Error::UnsupportedUrlScheme);
+        }
+
+        Ok(())
+    }
+
+    /// Check that a drop has a supply backed by its items, non-zero wallet limit and token
+    /// price in a token of SPL Token or Token-2022, valid and unique item names, valid URLs, an
+    /// end after its go-live timestamp and allowlist phases ordered before it
+    pub fn validate_drop_settings(settings: &DropSettings) -> Result<(), Error> {
+        if settings.max_supply == 0
+            || settings.wallet_limit == Some(0)
+            || settings
+                .token_payment
+                .as_ref()
+                .is_some_and(|token_payment| {
+                    token_payment.amount == 0
+                        || (!spl_token::check_id(&token_payment.token_program)
+                            && !spl_token_2022::check_id(&token_payment.token_program))
+                })
+        {
+            return Err(Error::InvalidDrop);
+        }
+
+        if let (Some(go_live), Some(end)) = (settings.go_live_timestamp, settings.end_timestamp) {
+            if end <= go_live {
+                return Err(Error::InvalidDrop);
+            }
+        }
+
+        if !settings.phases.is_empty() {
+            //phases end at the go-live
+            let go_live = settings.go_live_timestamp.ok_or(Error::InvalidDrop)?;
+            if settings.phases.len() > MAX_DROP_PHASES
+                || settings.phases.iter().any(|phase| phase.limit == 0)
+                || settings
+                    .phases
+                    .windows(2)
+                    .any(|pair| pair[0].start_timestamp >= pair[1].start_timestamp)
+                || settings
+                    .phases
+                    .last()
+                    .is_some_and(|phase| phase.start_timestamp >= go_live)
+            {
+                return Err(Error::InvalidDrop);
+            }
+        }
+
+        match &settings.items {
+            DropItems::List(items) => {
+                if (items.len() as u64) < settings.max_supply {
+                    return Err(Error::InvalidDrop);
+                }
+                //items with the same normalized name would share their mint PDA
+                let mut names = std::collections::BTreeSet::new();
+                for (name, url) in items {
+                    Self::validate_name(name)?;
+                    Self::validate_url(url, ALLOWED_URL_SCHEMES)?;
+                    if !names.insert(crate::instructions::normalize_nft_name(name)) {
+                        return Err(Error::InvalidDrop);
+                    }
+                }
+            }
+            DropItems::Template(url) => {
+                //the last item has the longest URL
+                let url = url.replace(DROP_URL_PLACEHOLDER, &settings.max_supply.to_string());
+                Self::validate_url(&url, ALLOWED_URL_SCHEMES)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the royalties are at most 100% and that the creators are at most
+    /// `MAX_CREATORS`, all different and with shares adding up to 100%
+    pub fn validate_creators(
+        creators: &[Creator],
+        seller_fee_basis_points: u16,
+    ) -> Result<(), Error> {
+        if seller_fee_basis_points > MAX_BASIS_POINTS {
+            return Err(Error::InvalidSellerFee);
+        }
+
+        if creators.is_empty() {
+            return Ok(());
+        }
+        if creators.len() > MAX_CREATORS {
+            return Err(Error::TooManyCreators);
+        }
+
+        let mut total_share = 0u32;
+        for (i, creator) in creators.iter().enumerate() {
+            if creators[..i].iter().any(|c| c.address == creator.address) {
+                return Err(Error::DuplicateCreator);
+            }
+            total_share += u32::from(creator.share);
+        }
+        if total_share != u32::from(MAX_BASIS_POINTS) {
+            return Err(Error::InvalidCreatorShares);
+        }
+
+        Ok(())
+    }
+
+    //reads the metadata of `mint`, checking that `metadata_account` is its PDA
+    fn load_metadata(
+        program_id: &Pubkey,
+        mint: &Pubkey,
+        metadata_account: &AccountInfo,
+    ) -> Result<Metadata, ProgramError> {
+        let (metadata_pda, _) = crate::instructions::derive_metadata_account(program_id, mint);
+        Self::load_account(program_id, &metadata_pda, metadata_account)
+    }
+
+    //reads an account owned by this program, checking that it's the `expected` PDA
+    fn load_account<T: ProgramAccount>(
+        program_id: &Pubkey,
+        expected: &Pubkey,
+        account: &AccountInfo,
+    ) -> Result<T, ProgramError> {
+        if expected != account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        if account.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+
+        Self::unpack_account(account)
+    }
+
+    //decodes an account owned by this program, checking that it's of the kind of `T`
+    fn unpack_account<T: ProgramAccount>(account: &AccountInfo) -> Result<T, ProgramError> {
+        let data = account.data.borrow();
+        if data.first() != Some(&(T::ACCOUNT_TYPE as u8)) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        T::try_from_slice(&data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    //closes an account owned by this program, moving its lamports to `recipient`
+    fn close_program_account(account: &AccountInfo, recipient: &AccountInfo) -> ProgramResult {
+        let recipient_lamports = recipient
+            .lamports()
+            .checked_add(account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        **recipient.lamports.borrow_mut() = recipient_lamports;
+        **account.lamports.borrow_mut() = 0;
+        account.data.borrow_mut().fill(0);
+
+        Ok(())
+    }
+
+    //creates the metadata PDA of `metadata.mint` and stores `metadata` in it
+    fn create_metadata_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        metadata_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        metadata: &Metadata,
+    ) -> ProgramResult {
+        //verify that the metadata account matches the PDA for this NFT
+        let (metadata_pda, metadata_pda_bump_seed) =
+            crate::instructions::derive_metadata_account(program_id, &metadata.mint);
+        if &metadata_pda != metadata_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        // create metadata_seeds (for invoke_signed)
+        let metadata_seeds_partial =
+            &crate::instructions::derive_metadata_account_seeds(program_id, &metadata.mint)[..];
+
+        let mut metadata_seeds = [&[] as &_; 5];
+        metadata_seeds[..4].copy_from_slice(metadata_seeds_partial);
+
+        let metadata_pda_bump_seed = [metadata_pda_bump_seed];
+        metadata_seeds[4] = &metadata_pda_bump_seed[..];
+
+        msg!("Calling the system program to create the metadata account...");
+        Self::create_program_account(
+            program_id,
+            payer,
+            metadata_account,
+            system_program,
+            rent,
+            &metadata_seeds,
+            &metadata.try_to_vec()?,
+        )
+    }
+
+    //rejects `name` if it was registered by the minter of another NFT than `mint`
+    fn check_name_available(
+        program_id: &Pubkey,
+        name_record_account: &AccountInfo,
+        name: &str,
+        mint: &Pubkey,
+    ) -> ProgramResult {
+        let (name_record_pda, _) =
+            crate::instructions::derive_name_record_account(program_id, name);
+        if &name_record_pda != name_record_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        if name_record_account.owner != program_id {
+            return Ok(());
+        }
+
+        let name_record: NameRecord =
+            Self::load_account(program_id, &name_record_pda, name_record_account)?;
+        if &name_record.mint != mint {
+            return Err(Error::NameAlreadyRegistered.into());
+        }
+
+        Ok(())
+    }
+
+    //creates the record of the normalized `name`, failing if another NFT registered it
+    fn create_name_record<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        name_record_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        name: &str,
+        mint: &Pubkey,
+    ) -> ProgramResult {
+        //verify that the name record account matches the PDA for this name
+        let (name_record_pda, name_record_pda_bump_seed) =
+            crate::instructions::derive_name_record_account(program_id, name);
+        if &name_record_pda != name_record_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        if name_record_account.owner == program_id {
+            return Err(Error::NameAlreadyRegistered.into());
+        }
+
+        // create name_record_seeds (for invoke_signed)
+        let name_seed = crate::instructions::hash_nft_name(name);
+        let name_record_seeds_partial =
+            &crate::instructions::derive_name_record_account_seeds(program_id, &name_seed)[..];
+
+        let mut name_record_seeds = [&[] as &_; 6];
+        name_record_seeds[..5].copy_from_slice(name_record_seeds_partial);
+
+        let name_record_pda_bump_seed = [name_record_pda_bump_seed];
+        name_record_seeds[5] = &name_record_pda_bump_seed[..];
+
+        let name_record = NameRecord {
+            account_type: AccountType::NameRecord,
+            version: NAME_RECORD_VERSION,
+            name: name.to_string(),
+            mint: *mint,
+            registrant: *payer.key,
+        };
+
+        msg!("Calling the system program to create the name record account...");
+        Self::create_program_account(
+            program_id,
+            payer,
+            name_record_account,
+            system_program,
+            rent,
+            &name_record_seeds,
+            &name_record.try_to_vec()?,
+        )
+    }
+
+    //checks that `minter` holds an NFT accepted by the token gate of `drop`,
+    // then burns it or records it as used if the gate asks to
+    fn pass_token_gate<'a>(
+        program_id: &Pubkey,
+        token_gate: &TokenGate,
+        accounts: &TokenGateAccounts<'a, '_>,
+        minter: &AccountInfo<'a>,
+        drop: &Pubkey,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+    ) -> ProgramResult {
+        let TokenGateAccounts {
+            token_account,
+            mint,
+            token_program,
+            metadata,
+            record,
+        } = *accounts;
+
+        //check that `mint` and `token_account` are accounts of the token program
+        Self::check_token_program(token_program)?;
+        if mint.owner != token_program.key || token_account.owner != token_program.key {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let account = Self::unpack_token_account(token_account)?;
+        if &account.owner != minter.key || &account.mint != mint.key || account.amount == 0 {
+            return Err(Error::TokenGateNotPassed.into());
+        }
+
+        let accepted = match &token_gate.requirement {
+            GateRequirement::Mint(required_mint) => required_mint == mint.key,
+            GateRequirement::Collection(collection) => {
+                let metadata_account_info = metadata.ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let (metadata_pda, _) =
+                    crate::instructions::derive_metadata_account(program_id, mint.key);
+                let metadata: Metadata =
+                    Self::load_account(program_id, &metadata_pda, metadata_account_info)?;
+                metadata.collection
+                    == Some(CollectionItem {
+                        key: *collection,
+                        verified: true,
+                    })
+            }
+        };
+        if !accepted {
+            return Err(Error::TokenGateNotPassed.into());
+        }
+
+        match token_gate.action {
+            GateAction::Keep => Ok(()),
+            GateAction::Burn => {
+                let burn_ix = spl_token_2022::instruction::burn(
+                    token_program.key,
+                    token_account.key,
+                    mint.key,
+                    minter.key,
+                    &[],
+                    1,
+                )?;
+
+                msg!("Calling the token program to burn the gate NFT...");
+                invoke(
+                    &burn_ix,
+                    &[
+                        token_account.clone(),
+                        mint.clone(),
+                        minter.clone(),
+                        token_program.clone(),
+                    ],
+                )
+            }
+            GateAction::MarkUsed => {
+                let record = record.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+                //verify that the gate record account matches the PDA for this NFT
+                let (record_pda, record_pda_bump_seed) =
+                    crate::instructions::derive_gate_record_account(program_id, drop, mint.key);
+                if &record_pda != record.key {
+                    return Err(Error::AccountMismatch.into());
+                }
+                if record.owner == program_id {
+                    return Err(Error::GateNftAlreadyUsed.into());
+                }
+
+                // create record_seeds (for invoke_signed)
+                let record_seeds_partial = &crate::instructions::derive_gate_record_account_seeds(
+                    program_id, drop, mint.key,
+                )[..];
+
+                let mut record_seeds = [&[] as &_; 6];
+                record_seeds[..5].copy_from_slice(record_seeds_partial);
+
+                let record_pda_bump_seed = [record_pda_bump_seed];
+                record_seeds[5] = &record_pda_bump_seed[..];
+
+                let gate_record = GateRecord {
+                    account_type: AccountType::GateRecord,
+                    version: GATE_RECORD_VERSION,
+                    drop: *drop,
+                    mint: *mint.key,
+                    minter: *minter.key,
+                };
+
+                msg!("Calling the system program to create the gate record account...");
+                Self::create_program_account(
+                    program_id,
+                    minter,
+                    record,
+                    system_program,
+                    rent,
+                    &record_seeds,
+                    &gate_record.try_to_vec()?,
+                )
+            }
+        }
+    }
+
+    //counts an item of `drop` minted by `minter` against the per wallet `limit`,
+    // creating its mint counter PDA for the first one. Nothing is counted without a limit
+    fn count_wallet_mint<'a>(
+        program_id: &Pubkey,
+        minter: &AccountInfo<'a>,
+        drop: &Pubkey,
+        mint_counter_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        limit: Option<u64>,
+    ) -> ProgramResult {
+        //verify that the mint counter account matches the PDA for this minter
+        let (mint_counter_pda, mint_counter_pda_bump_seed) =
+            crate::instructions::derive_mint_counter_account(program_id, drop, minter.key);
+        if &mint_counter_pda != mint_counter_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        let limit = match limit {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        let mut mint_counter = if mint_counter_account.owner == program_id {
+            Self::load_account(program_id, &mint_counter_pda, mint_counter_account)?
+        } else {
+            MintCounter {
+                account_type: AccountType::MintCounter,
+                version: MINT_COUNTER_VERSION,
+                drop: *drop,
+                minter: *minter.key,
+                count: 0,
+            }
+        };
+
+        if mint_counter.count >= limit {
+            return Err(Error::WalletLimitReached.into());
+        }
+        mint_counter.count += 1;
+
+        if mint_counter_account.owner == program_id {
+            return Self::store_account(&mint_counter, mint_counter_account);
+        }
+
+        // create mint_counter_seeds (for invoke_signed)
+        let mint_counter_seeds_partial = &crate::instructions::derive_mint_counter_account_seeds(
+            program_id, drop, minter.key,
+        )[..];
+
+        let mut mint_counter_seeds = [&[] as &_; 6];
+        mint_counter_seeds[..5].copy_from_slice(mint_counter_seeds_partial);
+
+        let mint_counter_pda_bump_seed = [mint_counter_pda_bump_seed];
+        mint_counter_seeds[5] = &mint_counter_pda_bump_seed[..];
+
+        msg!("Calling the system program to create the mint counter account...");
+        Self::create_program_account(
+            program_id,
+            minter,
+            mint_counter_account,
+            system_program,
+            rent,
+            &mint_counter_seeds,
+            &mint_counter.try_to_vec()?,
+        )
+    }
+
+    //counts an item minted by `minter` during the allowlist phase `phase` of `drop`,
+    // creating its redemption PDA for the first one
+    #[allow(clippy::too_many_arguments)]
+    fn redeem_allowlist<'a>(
+        program_id: &Pubkey,
+        minter: &AccountInfo<'a>,
+        drop: &Pubkey,
+        redemption_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        phase: u8,
+        limit: u64,
+    ) -> ProgramResult {
+        //verify that the redemption account matches the PDA for this minter and phase
+        let (redemption_pda, redemption_pda_bump_seed) =
+            crate::instructions::derive_redemption_account(program_id, drop, minter.key, phase);
+
+        if redemption_account.owner == program_id {
+            let mut redemption: Redemption =
+                Self::load_account(program_id, &redemption_pda, redemption_account)?;
+            if redemption.count >= limit {
+                return Err(Error::AllowlistLimitReached.into());
+            }
+
+            redemption.count += 1;
+            return Self::store_account(&redemption, redemption_account);
+        }
+
+        if &redemption_pda != redemption_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        if limit == 0 {
+            return Err(Error::AllowlistLimitReached.into());
+        }
+
+        // create redemption_seeds (for invoke_signed)
+        let phase_seed = [phase];
+        let redemption_seeds_partial = &crate::instructions::derive_redemption_account_seeds(
+            program_id,
+            drop,
+            minter.key,
+            &phase_seed,
+        )[..];
+
+        let mut redemption_seeds = [&[] as &_; 7];
+        redemption_seeds[..6].copy_from_slice(redemption_seeds_partial);
+
+        let redemption_pda_bump_seed = [redemption_pda_bump_seed];
+        redemption_seeds[6] = &redemption_pda_bump_seed[..];
+
+        let redemption = Redemption {
+            account_type: AccountType::Redemption,
+            version: REDEMPTION_VERSION,
+            drop: *drop,
+            minter: *minter.key,
+            phase,
+            count: 1,
+        };
+
+        msg!("Calling the system program to create the redemption account...");
+        Self::create_program_account(
+            program_id,
+            minter,
+            redemption_account,
+            system_program,
+            rent,
+            &redemption_seeds,
+            &redemption.try_to_vec()?,
+        )
+    }
+
+    //writes back `value` to its account, which must keep the same size
+    fn store_account<T: BorshSerialize>(value: &T, account: &AccountInfo) -> ProgramResult {
+        let data = value.try_to_vec()?;
+        if data.len() != account.data_len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        account.data.borrow_mut().copy_from_slice(&data);
+
+        Ok(())
+    }
+
+    //writes back `value` to its account, resizing it and keeping it rent exempt,
+    // `payer` funds the extra rent or gets back the excess lamports
+    fn store_resized_account<'a, T: BorshSerialize>(
+        value: &T,
+        account: &AccountInfo<'a>,
+        payer: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+    ) -> ProgramResult {
+        let data = value.try_to_vec()?;
+
+        let required_lamports = rent.minimum_balance(data.len());
+        let current_lamports = account.lamports();
+        if required_lamports > current_lamports {
+            let transfer_ix = solana_program::system_instruction::transfer(
+                payer.key,
+                account.key,
+                required_lamports - current_lamports,
+            );
+
+            msg!("Calling the system program to fund the resized account...");
+            invoke(
+                &transfer_ix,
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        } else if current_lamports > required_lamports {
+            let payer_lamports = payer
+                .lamports()
+                .checked_add(current_lamports - required_lamports)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            **payer.lamports.borrow_mut() = payer_lamports;
+            **account.lamports.borrow_mut() = required_lamports;
+        }
+
+        account.realloc(data.len(), false)?;
+        account.data.borrow_mut().copy_from_slice(&data);
+
+        Ok(())
+    }
+
+    //creates the master edition of `mint` and the edition marker tracking its prints
+    #[allow(clippy::too_many_arguments)]
+    fn create_master_edition<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        mint: &Pubkey,
+        master_edition_account: &AccountInfo<'a>,
+        edition_marker_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        max_supply: u64,
+    ) -> ProgramResult {
+        //verify that the accounts match the PDAs for this NFT
+        let (master_edition_pda, master_edition_pda_bump_seed) =
+            crate::instructions::derive_master_edition_account(program_id, mint);
+        if &master_edition_pda != master_edition_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+        let (edition_marker_pda, edition_marker_pda_bump_seed) =
+            crate::instructions::derive_edition_marker_account(program_id, mint);
+        if &edition_marker_pda != edition_marker_account.key {
+            return Err(Error::AccountMismatch.into());
+        }
+
+        // create master_edition_seeds (for invoke_signed)
+        let master_edition_seeds_partial =
+            &crate::instructions::derive_master_edition_account_seeds(program_id, mint)[..];
+
+        let mut master_edition_seeds = [&[] as &_; 5];
+        master_edition_seeds[..4].copy_from_slice(master_edition_seeds_partial);
+
+        let master_edition_pda_bump_seed = [master_edition_pda_bump_seed];
+        master_edition_seeds[4] = &master_edition_pda_bump_seed[..];
+
+        // create edition_marker_seeds (for invoke_signed)
+        let edition_marker_seeds_partial =
+            &crate::instructions::derive_edition_marker_account_seeds(program_id, mint)[..];
+
+        let mut edition_marker_seeds = [&[] as &_; 5];
+        edition_marker_seeds[..4].copy_from_slice(edition_marker_seeds_partial);
+
+        let edition_marker_pda_bump_seed = [edition_marker_pda_bump_seed];
+        edition_marker_seeds[4] = &edition_marker_pda_bump_seed[..];
+
+        let master_edition = MasterEdition {
+            account_type: AccountType::MasterEdition,
+            version: MASTER_EDITION_VERSION,
+            mint: *mint,
+            max_supply,
+            supply: 0,
+        };
+
+        msg!("Calling the system program to create the master edition account...");
+        Self::create_program_account(
+            program_id,
+            payer,
+            master_edition_account,
+            system_program,
+            rent,
+            &master_edition_seeds,
+            &master_edition.try_to_vec()?,
+        )?;
+
+        let edition_marker = EditionMarker {
+            account_type: AccountType::EditionMarker,
+            version: MASTER_EDITION_VERSION,
+            master_mint: *mint,
+            bitmap: vec![0; max_supply.div_ceil(8) as usize],
+        };
+
+        msg!("Calling the system program to create the edition marker account...");
+        Self::create_program_account(
+            program_id,
+            payer,
+            edition_marker_account,
+            system_program,
+            rent,
+            &edition_marker_seeds,
+            &edition_marker.try_to_vec()?,
+        )
+    }
+
+    //creates a PDA owned by this program, signed with `seeds`, and stores `data` in it
+    fn create_program_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        seeds: &[&[u8]],
+        data: &[u8],
+    ) -> ProgramResult {
+        Self::create_pda_account(
+            payer,
+            account,
+            system_program,
+            rent,
+            data.len(),
+            program_id,
+            seeds,
+        )?;
+
+        account.data.borrow_mut().copy_from_slice(data);
+
+        Ok(())
+    }
+
+    //creates a PDA of `space` bytes owned by `owner`, signed with `seeds`,
+    // topping up its rent instead if it was already funded, as `create_account` rejects
+    // any account holding lamports and anyone can transfer some to a PDA
+    fn create_pda_account<'a>(
+        payer: &AccountInfo<'a>,
+        account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        rent: &Rent,
+        space: usize,
+        owner: &Pubkey,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        if account.lamports() == 0 {
+            let create_account_ix = solana_program::system_instruction::create_account(
+                payer.key,
+                account.key,
+                rent.minimum_balance(space),
+                space as u64,
+                owner,
+            );
+
+            return invoke_signed(
+                &create_account_ix,
+                &[payer.clone(), account.clone(), system_program.clone()],
+                &[seeds],
+            );
+        }
+
+        let shortfall = rent
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if shortfall > 0 {
+            let transfer_ix =
+                solana_program::system_instruction::transfer(payer.key, account.key, shortfall);
+            invoke(
+                &transfer_ix,
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        }
+
+        let allocate_ix = solana_program::system_instruction::allocate(account.key, space as u64);
+        invoke_signed(
+            &allocate_ix,
+            &[account.clone(), system_program.clone()],
+            &[seeds],
+        )?;
+
+        let assign_ix = solana_program::system_instruction::assign(account.key, owner);
+        invoke_signed(
+            &assign_ix,
+            &[account.clone(), system_program.clone()],
+            &[seeds],
+        )
+    }
 }
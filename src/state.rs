@@ -0,0 +1,436 @@
+use solana_program::pubkey::Pubkey;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+// No layout has been released before the current ones, which all ship as version 1:
+// bump the version of an account whenever its layout changes after a release.
+
+/// Current version of the `Metadata` layout
+pub const METADATA_VERSION: u8 = 1;
+
+/// Current version of the `Collection` layout
+pub const COLLECTION_VERSION: u8 = 1;
+
+/// Current version of the `MasterEdition` and `EditionMarker` layouts
+pub const MASTER_EDITION_VERSION: u8 = 1;
+
+/// Current version of the `NameRecord` layout
+pub const NAME_RECORD_VERSION: u8 = 1;
+
+/// Current version of the `Config` layout
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Current version of the `NftDrop` layout
+pub const DROP_VERSION: u8 = 1;
+
+/// Current version of the `Redemption` layout
+pub const REDEMPTION_VERSION: u8 = 1;
+
+/// Current version of the `MintCounter` layout
+pub const MINT_COUNTER_VERSION: u8 = 1;
+
+/// Current version of the `GateRecord` layout
+pub const GATE_RECORD_VERSION: u8 = 1;
+
+/// Maximum number of allowlist phases of a drop
+pub const MAX_DROP_PHASES: usize = 8;
+
+/// Placeholder replaced by the item number in the URL template of a drop
+pub const DROP_URL_PLACEHOLDER: &str = "{index}";
+
+/// Maximum number of prints of a master edition
+pub const MAX_EDITION_SUPPLY: u64 = 10_000;
+
+/// Maximum length in bytes of the name of an NFT, and of its display name
+pub const MAX_NAME_LENGTH: usize = 64;
+
+/// Maximum length in bytes of the URL associated to an NFT
+pub const MAX_URL_LENGTH: usize = 200;
+
+/// URL schemes accepted when minting an NFT
+pub const ALLOWED_URL_SCHEMES: &[&str] = &["https", "ipfs", "ar"];
+
+/// Maximum number of creators of an NFT
+pub const MAX_CREATORS: usize = 5;
+
+/// Basis points making up 100%
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// Kind of an account owned by this program, stored as its first byte so that
+/// an account of one kind can't be passed where another kind is expected
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone, Copy)]
+pub enum AccountType {
+    /// Zeroed data, never stored
+    Uninitialized,
+    /// See `Metadata`
+    Metadata,
+    /// See `Collection`
+    Collection,
+    /// See `MasterEdition`
+    MasterEdition,
+    /// See `EditionMarker`
+    EditionMarker,
+    /// See `NameRecord`
+    NameRecord,
+    /// See `Config`
+    Config,
+    /// See `NftDrop`
+    Drop,
+    /// See `Redemption`
+    Redemption,
+    /// See `MintCounter`
+    MintCounter,
+    /// See `GateRecord`
+    GateRecord,
+}
+
+/// An account owned by this program, starting with its `AccountType`
+pub trait ProgramAccount: BorshDeserialize + BorshSerialize {
+    /// The first byte of the account
+    const ACCOUNT_TYPE: AccountType;
+}
+
+/// Information about a minted NFT, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Metadata {
+    /// Kind of the account, `AccountType::Metadata`
+    pub account_type: AccountType,
+    /// Version of the layout, see `METADATA_VERSION`
+    pub version: u8,
+    /// The mint of the NFT
+    pub mint: Pubkey,
+    /// The account that minted the NFT, or the drop PDA for drop items.
+    /// The mint and token account PDAs of the NFT are derived from it
+    pub minter: Pubkey,
+    /// The slot the NFT was minted at
+    pub created_slot: u64,
+    /// The name of the NFT
+    pub name: String,
+    /// The URL associated to the NFT
+    pub url: String,
+    /// The collection the NFT claims to belong to
+    pub collection: Option<CollectionItem>,
+    /// Royalties paid to the creators on secondary sales, in basis points
+    pub seller_fee_basis_points: u16,
+    /// The creators of the NFT
+    pub creators: Vec<Creator>,
+    /// The master edition the NFT was printed from, if any
+    pub edition: Option<Edition>,
+    /// The account allowed to update the metadata
+    pub update_authority: Pubkey,
+    /// Whether the metadata can still be updated, can't be set again once cleared
+    pub is_mutable: bool,
+    /// The name of the NFT to display, unlike `name` it can be updated
+    pub display_name: String,
+    /// The account allowed to freeze and thaw the NFT, if any
+    pub freeze_delegate: Option<Pubkey>,
+    /// Whether the token account holding the NFT is frozen
+    pub frozen: bool,
+    /// Whether the NFT can never leave the account of its owner,
+    /// only its minter can burn it through the mint permanent delegate
+    pub soulbound: bool,
+}
+
+impl ProgramAccount for Metadata {
+    const ACCOUNT_TYPE: AccountType = AccountType::Metadata;
+}
+
+/// Reference of a print to its master edition
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Edition {
+    /// The mint of the master edition
+    pub master_mint: Pubkey,
+    /// The edition number of the print, starting at 1
+    pub number: u64,
+}
+
+/// A creator of an NFT and its share of the royalties
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Creator {
+    /// The account of the creator
+    pub address: Pubkey,
+    /// Share of the royalties, in basis points
+    pub share: u16,
+    /// Whether the creator signed the NFT
+    pub verified: bool,
+}
+
+/// Membership of an NFT to a collection
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct CollectionItem {
+    /// The collection account
+    pub key: Pubkey,
+    /// Whether the collection authority verified the NFT belongs to the collection
+    pub verified: bool,
+}
+
+/// A group of NFTs, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Collection {
+    /// Kind of the account, `AccountType::Collection`
+    pub account_type: AccountType,
+    /// Version of the layout, see `COLLECTION_VERSION`
+    pub version: u8,
+    /// The account allowed to verify NFTs of the collection
+    pub authority: Pubkey,
+    /// The slot the collection was created at
+    pub created_slot: u64,
+    /// The name of the collection
+    pub name: String,
+    /// The URL associated to the collection
+    pub url: String,
+}
+
+impl ProgramAccount for Collection {
+    const ACCOUNT_TYPE: AccountType = AccountType::Collection;
+}
+
+/// An NFT that can be printed in numbered editions, stored in a PDA owned by this program.
+/// The PDA is also the mint authority of the master edition
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct MasterEdition {
+    /// Kind of the account, `AccountType::MasterEdition`
+    pub account_type: AccountType,
+    /// Version of the layout, see `MASTER_EDITION_VERSION`
+    pub version: u8,
+    /// The mint of the master edition
+    pub mint: Pubkey,
+    /// The maximum number of prints
+    pub max_supply: u64,
+    /// The number of prints so far
+    pub supply: u64,
+}
+
+impl ProgramAccount for MasterEdition {
+    const ACCOUNT_TYPE: AccountType = AccountType::MasterEdition;
+}
+
+/// The edition numbers already printed from a master edition, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct EditionMarker {
+    /// Kind of the account, `AccountType::EditionMarker`
+    pub account_type: AccountType,
+    /// Version of the layout, see `MASTER_EDITION_VERSION`
+    pub version: u8,
+    /// The mint of the master edition
+    pub master_mint: Pubkey,
+    /// One bit per edition number, set once printed
+    pub bitmap: Vec<u8>,
+}
+
+impl ProgramAccount for EditionMarker {
+    const ACCOUNT_TYPE: AccountType = AccountType::EditionMarker;
+}
+
+/// Registration of a normalized NFT name across all minters, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct NameRecord {
+    /// Kind of the account, `AccountType::NameRecord`
+    pub account_type: AccountType,
+    /// Version of the layout, see `NAME_RECORD_VERSION`
+    pub version: u8,
+    /// The name of the NFT, as minted
+    pub name: String,
+    /// The mint of the NFT holding the name
+    pub mint: Pubkey,
+    /// The account that minted the NFT and paid for the record
+    pub registrant: Pubkey,
+}
+
+impl ProgramAccount for NameRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::NameRecord;
+}
+
+/// Program-wide settings, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Config {
+    /// Kind of the account, `AccountType::Config`
+    pub account_type: AccountType,
+    /// Version of the layout, see `CONFIG_VERSION`
+    pub version: u8,
+    /// The account allowed to change the config
+    pub admin: Pubkey,
+    /// Whether every instruction but the config ones is rejected
+    pub paused: bool,
+    /// Lamports paid to the treasury for each minted NFT
+    pub mint_fee: u64,
+    /// The treasury PDA collecting the mint fees
+    pub treasury: Pubkey,
+}
+
+impl ProgramAccount for Config {
+    const ACCOUNT_TYPE: AccountType = AccountType::Config;
+}
+
+/// A sale of NFTs minted one by one by the buyers, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct NftDrop {
+    /// Kind of the account, `AccountType::Drop`
+    pub account_type: AccountType,
+    /// Version of the layout, see `DROP_VERSION`
+    pub version: u8,
+    /// The account receiving the price of the items, update authority of their metadata
+    pub authority: Pubkey,
+    /// The slot the drop was created at
+    pub created_slot: u64,
+    /// The name of the drop
+    pub name: String,
+    /// The number of items minted so far
+    pub minted: u64,
+    /// The settings of the drop
+    pub settings: DropSettings,
+}
+
+impl ProgramAccount for NftDrop {
+    const ACCOUNT_TYPE: AccountType = AccountType::Drop;
+}
+
+/// Settings of a drop, fixed at its creation
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct DropSettings {
+    /// Lamports paid to the drop authority for each item
+    pub price: u64,
+    /// The maximum number of items
+    pub max_supply: u64,
+    /// The slot from which the items can be minted, if any
+    pub go_live_slot: Option<u64>,
+    /// The unix timestamp from which the items can be minted, if any
+    pub go_live_timestamp: Option<i64>,
+    /// The unix timestamp from which the items can't be minted anymore, if any
+    pub end_timestamp: Option<i64>,
+    /// The names and URLs of the items
+    pub items: DropItems,
+    /// Phases before `go_live_timestamp` restricted to allowlisted wallets, by start time
+    pub phases: Vec<AllowlistPhase>,
+    /// The maximum number of items minted by each wallet over the whole drop, if any.
+    /// Allowlist phases have their own limit on top of it
+    pub wallet_limit: Option<u64>,
+    /// The SPL token paid for each item on top of `price`, if any
+    pub token_payment: Option<TokenPayment>,
+    /// The NFT the buyers must hold to mint an item, if any
+    pub token_gate: Option<TokenGate>,
+}
+
+/// Price of the items of a drop in a token of SPL Token or Token-2022
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct TokenPayment {
+    /// The mint of the token, e.g. USDC
+    pub mint: Pubkey,
+    /// The token program of the mint
+    pub token_program: Pubkey,
+    /// The amount of tokens paid for each item, in base units
+    pub amount: u64,
+    /// The token account of the drop receiving the payments
+    pub treasury: Pubkey,
+}
+
+/// Requirement of a drop for the buyers to hold an NFT
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct TokenGate {
+    /// The NFTs accepted
+    pub requirement: GateRequirement,
+    /// What happens to the NFT presented for each item
+    pub action: GateAction,
+}
+
+/// NFTs accepted by a token gate
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub enum GateRequirement {
+    /// The NFT of the given mint
+    Mint(Pubkey),
+    /// Any NFT verified as member of the given collection
+    Collection(Pubkey),
+}
+
+/// What happens to the NFT presented to a token gate
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone, Copy)]
+pub enum GateAction {
+    /// The NFT can be presented again
+    Keep,
+    /// The NFT is recorded as used and can't be presented again to the drop
+    MarkUsed,
+    /// The NFT is burned by the token program, leaving its metadata as is
+    Burn,
+}
+
+/// A period of a drop restricted to the wallets of a Merkle tree, see `AllowlistTree`
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct AllowlistPhase {
+    /// The unix timestamp the phase starts at, it lasts until the next phase
+    /// or the go-live of the drop
+    pub start_timestamp: i64,
+    /// The Merkle root of the allowlisted wallets
+    pub merkle_root: [u8; 32],
+    /// The maximum number of items minted by each wallet during the phase
+    pub limit: u64,
+}
+
+/// Names and URLs of the items of a drop
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub enum DropItems {
+    /// Name and URL of each item, in minting order
+    List(Vec<(String, String)>),
+    /// Items named after the drop and their number starting at 1, with the URL
+    /// replacing `DROP_URL_PLACEHOLDER` by the number
+    Template(String),
+}
+
+/// Number of items minted by a wallet during an allowlist phase of a drop,
+/// stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Redemption {
+    /// Kind of the account, `AccountType::Redemption`
+    pub account_type: AccountType,
+    /// Version of the layout, see `REDEMPTION_VERSION`
+    pub version: u8,
+    /// The drop minted from
+    pub drop: Pubkey,
+    /// The allowlisted wallet
+    pub minter: Pubkey,
+    /// The index of the phase in the drop settings
+    pub phase: u8,
+    /// The number of items minted so far
+    pub count: u64,
+}
+
+impl ProgramAccount for Redemption {
+    const ACCOUNT_TYPE: AccountType = AccountType::Redemption;
+}
+
+/// Number of items minted by a wallet from a drop, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct MintCounter {
+    /// Kind of the account, `AccountType::MintCounter`
+    pub account_type: AccountType,
+    /// Version of the layout, see `MINT_COUNTER_VERSION`
+    pub version: u8,
+    /// The drop minted from
+    pub drop: Pubkey,
+    /// The minting wallet
+    pub minter: Pubkey,
+    /// The number of items minted so far
+    pub count: u64,
+}
+
+impl ProgramAccount for MintCounter {
+    const ACCOUNT_TYPE: AccountType = AccountType::MintCounter;
+}
+
+/// NFT used to pass the token gate of a drop, stored in a PDA owned by this program
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq, Clone)]
+pub struct GateRecord {
+    /// Kind of the account, `AccountType::GateRecord`
+    pub account_type: AccountType,
+    /// Version of the layout, see `GATE_RECORD_VERSION`
+    pub version: u8,
+    /// The token gated drop
+    pub drop: Pubkey,
+    /// The mint of the NFT used
+    pub mint: Pubkey,
+    /// The wallet that presented the NFT
+    pub minter: Pubkey,
+}
+
+impl ProgramAccount for GateRecord {
+    const ACCOUNT_TYPE: AccountType = AccountType::GateRecord;
+}